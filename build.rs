@@ -0,0 +1,19 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    generate_c_header();
+}
+
+/// Generate the C header for the FFI module into the build output. A copy
+/// is kept in `include/spayd.h` and the tests check that it is up to date.
+#[cfg(feature = "ffi")]
+fn generate_c_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    cbindgen::generate(&crate_dir)
+        .expect("couldn't generate the C header")
+        .write_to_file(format!("{out_dir}/spayd.h"));
+}
@@ -0,0 +1,213 @@
+#ifndef SPAYD_H
+#define SPAYD_H
+
+/* This file is generated by cbindgen, do not edit it by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Status codes returned by the C API.
+ */
+typedef enum SpaydStatus {
+  /**
+   * The call succeeded.
+   */
+  SPAYD_STATUS_OK = 0,
+  /**
+   * The text couldn't be parsed as a SPAYD.
+   */
+  SPAYD_STATUS_PARSE_ERROR = 1,
+  /**
+   * A field required by the SPAYD standard is missing.
+   */
+  SPAYD_STATUS_REQUIRED_FIELD_MISSING = 2,
+  /**
+   * The CRC32 checksum failed.
+   */
+  SPAYD_STATUS_CRC32_FAILED = 3,
+  /**
+   * A field value has an incorrect format.
+   */
+  SPAYD_STATUS_CONVERT_ERROR = 4,
+  /**
+   * The requested field isn't present.
+   */
+  SPAYD_STATUS_FIELD_MISSING = 5,
+  /**
+   * The major version of the SPAYD isn't supported.
+   */
+  SPAYD_STATUS_UNSUPPORTED_VERSION = 6,
+  /**
+   * A field name isn't allowed by the standard.
+   */
+  SPAYD_STATUS_INVALID_KEY = 7,
+  /**
+   * A field value contains a malformed percent escape.
+   */
+  SPAYD_STATUS_MALFORMED_ESCAPE = 8,
+  /**
+   * A field value's percent escapes don't decode to valid UTF-8.
+   */
+  SPAYD_STATUS_INVALID_ESCAPED_UTF8 = 9,
+  /**
+   * The amount has more decimal places than its currency allows.
+   */
+  SPAYD_STATUS_AMOUNT_PRECISION = 10,
+  /**
+   * A file couldn't be read.
+   */
+  SPAYD_STATUS_IO = 11,
+  /**
+   * A file or document isn't in the expected format.
+   */
+  SPAYD_STATUS_FILE_FORMAT = 12,
+  /**
+   * A payment can't be written in a file format.
+   */
+  SPAYD_STATUS_EXPORT_ERROR = 13,
+  /**
+   * A field value contains characters which aren't allowed.
+   */
+  SPAYD_STATUS_INVALID_VALUE = 14,
+  /**
+   * A required pointer argument was null.
+   */
+  SPAYD_STATUS_NULL_POINTER = 100,
+  /**
+   * A string argument wasn't valid UTF-8.
+   */
+  SPAYD_STATUS_INVALID_UTF8 = 101,
+  /**
+   * A string argument or value contained an interior nul byte.
+   */
+  SPAYD_STATUS_INTERIOR_NUL = 102,
+} SpaydStatus;
+
+/**
+ * A payment amount stored as a whole number of minor units (hundredths of
+ * the currency unit). This type doesn't need any other crates and only
+ * holds amounts which can be written in the AM field: no more than 10
+ * characters, no sign and at most 2 decimal places.
+ *
+ * Amounts are formatted with two decimal places ("480.50"), unless this
+ * would be too long for the AM field. Then trailing zeros are dropped from
+ * the fraction ("12345678.5").
+ */
+typedef struct Amount Amount;
+
+/**
+ * A Short Payment Descriptor structure containint the details of
+ * a requested payment.
+ */
+typedef struct Spayd Spayd;
+
+
+
+
+
+/**
+ * Parse and validate SPAYD text. On success a new handle is written to
+ * `out` and must be released with `spayd_free`.
+ *
+ * # Safety
+ * `text` must be a valid nul terminated string and `out` must be valid
+ * for writes.
+ */
+enum SpaydStatus spayd_parse(const char *text, struct Spayd **out);
+
+/**
+ * Create an empty version 1.0 SPAYD. The handle must be released with
+ * `spayd_free`.
+ */
+struct Spayd *spayd_new_v1_0(void);
+
+/**
+ * Release a SPAYD handle. Passing null is allowed and does nothing.
+ *
+ * # Safety
+ * `spayd` must be null or a handle returned by this library which hasn't
+ * already been freed.
+ */
+void spayd_free(struct Spayd *spayd);
+
+/**
+ * Release a string returned by this library. Passing null is allowed and
+ * does nothing.
+ *
+ * # Safety
+ * `text` must be null or a string returned by this library which hasn't
+ * already been freed.
+ */
+void spayd_string_free(char *text);
+
+/**
+ * Validate the SPAYD as `Spayd::validate` does. The status names the first
+ * problem found, matching the error kind (`SpaydError::kind`) of the Rust
+ * API:
+ * - `SPAYD_STATUS_INVALID_KEY` or `SPAYD_STATUS_INVALID_VALUE` if a field
+ *   name or value isn't allowed, e.g. an empty value,
+ * - `SPAYD_STATUS_UNSUPPORTED_VERSION` if the major version isn't known,
+ * - `SPAYD_STATUS_REQUIRED_FIELD_MISSING` if a required field is missing,
+ * - `SPAYD_STATUS_AMOUNT_PRECISION` if the amount has more decimal places
+ *   than its currency allows,
+ * - `SPAYD_STATUS_CRC32_FAILED` if the CRC32 field doesn't match.
+ *
+ * # Safety
+ * `spayd` must be a valid handle.
+ */
+enum SpaydStatus spayd_validate(const struct Spayd *spayd);
+
+/**
+ * Get the value of a field. The value is written to `out` and must be
+ * released with `spayd_string_free`. Returns `SPAYD_STATUS_FIELD_MISSING`
+ * when the field isn't present.
+ *
+ * # Safety
+ * `spayd` must be a valid handle, `key` a valid nul terminated string and
+ * `out` must be valid for writes.
+ */
+enum SpaydStatus spayd_get_field(const struct Spayd *spayd, const char *key, char **out);
+
+/**
+ * Set the value of a field. Returns `SPAYD_STATUS_INVALID_KEY` if the key
+ * isn't a valid field name.
+ *
+ * # Safety
+ * `spayd` must be a valid handle, `key` and `value` must be valid nul
+ * terminated strings.
+ */
+enum SpaydStatus spayd_set_field(struct Spayd *spayd, const char *key, const char *value);
+
+/**
+ * Format the SPAYD as text. The text is written to `out` and must be
+ * released with `spayd_string_free`.
+ *
+ * # Safety
+ * `spayd` must be a valid handle and `out` must be valid for writes.
+ */
+enum SpaydStatus spayd_to_string(const struct Spayd *spayd, char **out);
+
+#if defined(SPAYD_CRC32)
+/**
+ * Perform a CRC32 integrity check. As the CRC32 field is optional, the
+ * check passes when no checksum is present. If `provided` isn't null, it
+ * is set to whether a checksum was present.
+ *
+ * # Safety
+ * `spayd` must be a valid handle and `provided` must be null or valid
+ * for writes.
+ */
+enum SpaydStatus spayd_check_crc32(const struct Spayd *spayd, bool *provided);
+#endif
+
+/**
+ * Get a static description of a status code. The returned string must
+ * not be freed. Codes which aren't a `SpaydStatus` give "unknown status".
+ */
+const char *spayd_status_message(int status);
+
+#endif  /* SPAYD_H */
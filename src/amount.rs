@@ -0,0 +1,325 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::{fields, Spayd, SpaydError};
+
+#[cfg(feature = "rust_decimal")]
+use rust_decimal::Decimal;
+
+/// Maximum length of the AM field.
+pub(crate) const MAX_LENGTH: usize = 10;
+
+/// Maximum number of decimal places allowed in the AM field.
+pub(crate) const MAX_DECIMALS: u32 = 2;
+
+/// Number of minor units in one major unit.
+const MINOR_PER_MAJOR: u64 = 10u64.pow(MAX_DECIMALS);
+
+/// A payment amount stored as a whole number of minor units (hundredths of
+/// the currency unit). This type doesn't need any other crates and only
+/// holds amounts which can be written in the AM field: no more than 10
+/// characters, no sign and at most 2 decimal places.
+///
+/// Amounts are formatted with two decimal places ("480.50"), unless this
+/// would be too long for the AM field. Then trailing zeros are dropped from
+/// the fraction ("12345678.5").
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Amount {
+    minor_units: u64,
+}
+
+impl Amount {
+    /// An amount of zero.
+    pub const ZERO: Self = Self { minor_units: 0 };
+
+    /// The largest amount which can be written in the AM field.
+    pub const MAX: Self = Self {
+        minor_units: 9_999_999_999 * MINOR_PER_MAJOR,
+    };
+
+    /// Create an amount from a number of minor units. Returns `None` if the
+    /// amount can't be written in the AM field.
+    pub fn from_minor_units(minor_units: u64) -> Option<Self> {
+        let amount = Self { minor_units };
+        if amount.to_string().len() <= MAX_LENGTH {
+            Some(amount)
+        } else {
+            None
+        }
+    }
+
+    /// Create an amount from major units and minor units, e.g. `new(480, 50)`
+    /// for 480.50. Returns `None` if `minor` is 100 or more or the amount can't
+    /// be written in the AM field.
+    pub fn new(major: u64, minor: u8) -> Option<Self> {
+        if u64::from(minor) >= MINOR_PER_MAJOR {
+            return None;
+        }
+        major
+            .checked_mul(MINOR_PER_MAJOR)?
+            .checked_add(minor.into())
+            .and_then(Self::from_minor_units)
+    }
+
+    /// The amount as a whole number of minor units.
+    pub fn minor_units(&self) -> u64 {
+        self.minor_units
+    }
+
+    /// The whole major units of the amount.
+    pub fn major(&self) -> u64 {
+        self.minor_units / MINOR_PER_MAJOR
+    }
+
+    /// The minor units left over after the major units.
+    pub fn minor(&self) -> u8 {
+        (self.minor_units % MINOR_PER_MAJOR) as u8
+    }
+
+    /// Add two amounts, returning `None` if the result is too large.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.minor_units
+            .checked_add(other.minor_units)
+            .and_then(Self::from_minor_units)
+    }
+
+    /// Subtract an amount, returning `None` if the result would be negative.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.minor_units
+            .checked_sub(other.minor_units)
+            .and_then(Self::from_minor_units)
+    }
+
+    /// Multiply by a whole number, returning `None` if the result is too large.
+    pub fn checked_mul(self, factor: u64) -> Option<Self> {
+        self.minor_units
+            .checked_mul(factor)
+            .and_then(Self::from_minor_units)
+    }
+}
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (major, minor) = (self.major(), self.minor());
+        let full = format!("{}.{:02}", major, minor);
+
+        if full.len() <= MAX_LENGTH {
+            write!(f, "{}", full)
+        } else if minor == 0 {
+            write!(f, "{}", major)
+        } else if minor % 10 == 0 {
+            write!(f, "{}.{}", major, minor / 10)
+        } else {
+            write!(f, "{}", full)
+        }
+    }
+}
+
+impl FromStr for Amount {
+    type Err = SpaydError;
+
+    /// Parse text in the format of the AM field: digits with an optional
+    /// decimal point followed by one or two digits. Signs, exponents and
+    /// grouping separators aren't allowed.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || SpaydError::ConvertError(text.into());
+
+        if text.len() > MAX_LENGTH {
+            return Err(error());
+        }
+
+        match text.split_once('.') {
+            Some((_, minor)) if minor.len() > MAX_DECIMALS as usize => Err(error()),
+            _ => parse_minor_units(text)
+                .map(|minor_units| Self { minor_units })
+                .ok_or_else(error),
+        }
+    }
+}
+
+/// Parse a decimal number such as "12500000.50" into minor units, without
+/// the length limit of the AM field. Used for amounts from bank statements,
+/// which may be larger than any payment. Digits after the second decimal
+/// place must be zeros.
+pub(crate) fn parse_minor_units(text: &str) -> Option<u64> {
+    let (major, minor) = match text.split_once('.') {
+        Some((major, minor)) if !minor.is_empty() => (major, minor),
+        Some(_) => return None,
+        None => (text, ""),
+    };
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if major.is_empty() || !all_digits(major) || !all_digits(minor) {
+        return None;
+    }
+
+    let (minor, rest) = minor.split_at(minor.len().min(MAX_DECIMALS as usize));
+    if rest.bytes().any(|b| b != b'0') {
+        return None;
+    }
+    let minor: u64 = format!("{minor:0<width$}", width = MAX_DECIMALS as usize)
+        .parse()
+        .ok()?;
+    major
+        .parse::<u64>()
+        .ok()?
+        .checked_mul(MINOR_PER_MAJOR)?
+        .checked_add(minor)
+}
+
+#[cfg(feature = "rust_decimal")]
+impl From<Amount> for Decimal {
+    fn from(amount: Amount) -> Self {
+        Decimal::new(amount.minor_units as i64, MAX_DECIMALS)
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl TryFrom<Decimal> for Amount {
+    type Error = SpaydError;
+
+    /// Convert a decimal without rounding. Fails if the decimal is negative,
+    /// has more than two significant decimal places or is too large.
+    fn try_from(value: Decimal) -> Result<Self, Self::Error> {
+        let error = || SpaydError::ConvertError(value.to_string());
+
+        if value.is_sign_negative() && !value.is_zero() {
+            return Err(error());
+        }
+        let minor_units = value
+            .checked_mul(Decimal::from(MINOR_PER_MAJOR))
+            .ok_or_else(error)?;
+        if !minor_units.fract().is_zero() {
+            return Err(error());
+        }
+        u64::try_from(minor_units)
+            .ok()
+            .and_then(Self::from_minor_units)
+            .ok_or_else(error)
+    }
+}
+
+impl Spayd {
+    /// Get the payment amount as a fixed-point `Amount`.
+    pub fn fixed_amount(&self) -> Result<Amount, SpaydError> {
+        self.field_converted(fields::AMOUNT, Amount::from_str)
+    }
+
+    /// Set the payment amount from a fixed-point `Amount`.
+    pub fn set_fixed_amount(&mut self, amount: Amount) {
+        self.set_field_converted(fields::AMOUNT, amount, |amount| amount.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!("480.50".parse(), Ok(Amount::new(480, 50).unwrap()));
+        assert_eq!("480.5".parse(), Ok(Amount::new(480, 50).unwrap()));
+        assert_eq!("480".parse(), Ok(Amount::new(480, 0).unwrap()));
+        assert_eq!("0.01".parse(), Ok(Amount::from_minor_units(1).unwrap()));
+        assert_eq!("9999999999".parse(), Ok(Amount::MAX));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for text in [
+            "",
+            ".",
+            "1.",
+            ".5",
+            "-1",
+            "+1",
+            "1e3",
+            "1,50",
+            "1.505",
+            "1 000",
+            "10000000000",
+            "1234567.890",
+        ] {
+            assert_eq!(
+                text.parse::<Amount>(),
+                Err(SpaydError::ConvertError(text.into())),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(Amount::new(480, 50).unwrap().to_string(), "480.50");
+        assert_eq!(Amount::new(480, 0).unwrap().to_string(), "480.00");
+        assert_eq!(Amount::new(0, 5).unwrap().to_string(), "0.05");
+        assert_eq!(
+            Amount::new(9_999_999, 99).unwrap().to_string(),
+            "9999999.99"
+        );
+        assert_eq!(
+            Amount::new(12_345_678, 50).unwrap().to_string(),
+            "12345678.5"
+        );
+        assert_eq!(Amount::MAX.to_string(), "9999999999");
+    }
+
+    #[test]
+    fn limits() {
+        assert_eq!(Amount::new(1, 100), None);
+        assert_eq!(Amount::new(12_345_678, 55), None);
+        assert_eq!(Amount::new(10_000_000_000, 0), None);
+        assert_eq!(Amount::from_minor_units(u64::MAX), None);
+    }
+
+    #[test]
+    fn unlimited_minor_units() {
+        assert_eq!(parse_minor_units("12500000.50"), Some(1_250_000_050));
+        assert_eq!(parse_minor_units("99999999999.9"), Some(9_999_999_999_990));
+        assert_eq!(parse_minor_units("1.500"), Some(150));
+        assert_eq!(parse_minor_units("7"), Some(700));
+        for text in ["", "1.", ".5", "1.505", "-1", "1,5", "99999999999999999999"] {
+            assert_eq!(parse_minor_units(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Amount::new(100, 50).unwrap();
+        let b = Amount::new(0, 75).unwrap();
+
+        assert_eq!(a.checked_add(b), Amount::new(101, 25));
+        assert_eq!(a.checked_sub(b), Amount::new(99, 75));
+        assert_eq!(b.checked_sub(a), None);
+        assert_eq!(a.checked_mul(3), Amount::new(301, 50));
+        assert_eq!(Amount::MAX.checked_add(b), None);
+    }
+
+    #[test]
+    fn spayd_field() {
+        let mut spayd = Spayd::empty_v1_0();
+        spayd.set_fixed_amount(Amount::new(250, 0).unwrap());
+        assert_eq!(spayd.field("AM"), Some("250.00"));
+        assert_eq!(spayd.fixed_amount().ok(), Amount::new(250, 0));
+
+        spayd.set_field("AM", "2.5e2");
+        assert_eq!(
+            spayd.fixed_amount(),
+            Err(SpaydError::ConvertError("2.5e2".into()))
+        );
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn decimal_conversion() {
+        let amount = Amount::new(480, 50).unwrap();
+        assert_eq!(Decimal::from(amount), Decimal::new(48050, 2));
+        assert_eq!(Amount::try_from(Decimal::new(4805, 1)), Ok(amount));
+        assert_eq!(Amount::try_from(Decimal::new(480500, 3)), Ok(amount));
+        assert!(Amount::try_from(Decimal::new(1005, 3)).is_err());
+        assert!(Amount::try_from(Decimal::new(-1, 0)).is_err());
+        assert_eq!(
+            Amount::try_from(Decimal::MAX),
+            Err(SpaydError::ConvertError(Decimal::MAX.to_string()))
+        );
+    }
+}
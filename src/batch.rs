@@ -0,0 +1,324 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{Spayd, SpaydError};
+
+const FORMAT: &str = "SPAYD batch";
+
+/// The result of parsing one line of a batch.
+#[derive(PartialEq, Debug)]
+pub struct BatchLine {
+    /// The line number, starting from 1.
+    pub line: usize,
+    /// The parsed SPAYD or the reason the line was rejected.
+    pub result: Result<Spayd, SpaydError>,
+}
+
+/// Counts of the lines in a batch by outcome, with failures grouped by
+/// error kind (see `SpaydError::kind`).
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct BatchSummary {
+    /// Number of lines with a SPAYD, not counting blank lines.
+    pub total: usize,
+    /// Number of lines parsed successfully.
+    pub parsed: usize,
+    /// Number of lines which failed.
+    pub failed: usize,
+    /// Number of failures for each error kind.
+    pub errors: BTreeMap<&'static str, usize>,
+}
+
+impl Display for BatchSummary {
+    /// A one line summary, e.g. "3 lines: 1 parsed, 2 failed (ParseError: 2)".
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} lines: {} parsed, {} failed",
+            self.total, self.parsed, self.failed
+        )?;
+        if !self.errors.is_empty() {
+            let errors: Vec<String> = self
+                .errors
+                .iter()
+                .map(|(kind, count)| format!("{}: {}", kind, count))
+                .collect();
+            write!(f, " ({})", errors.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The results of parsing a batch, in line order.
+#[derive(PartialEq, Debug)]
+pub struct BatchReport {
+    lines: Vec<BatchLine>,
+}
+
+impl BatchReport {
+    pub(crate) fn new(lines: Vec<BatchLine>) -> Self {
+        Self { lines }
+    }
+
+    /// The result for each non-blank line.
+    pub fn lines(&self) -> &[BatchLine] {
+        &self.lines
+    }
+
+    /// Take the results.
+    pub fn into_lines(self) -> Vec<BatchLine> {
+        self.lines
+    }
+
+    /// The successfully parsed SPAYDs with their line numbers.
+    pub fn payments(&self) -> impl Iterator<Item = (usize, &Spayd)> {
+        self.lines
+            .iter()
+            .filter_map(|l| l.result.as_ref().ok().map(|spayd| (l.line, spayd)))
+    }
+
+    /// The errors with their line numbers.
+    pub fn errors(&self) -> impl Iterator<Item = (usize, &SpaydError)> {
+        self.lines
+            .iter()
+            .filter_map(|l| l.result.as_ref().err().map(|error| (l.line, error)))
+    }
+
+    /// Count the results by outcome and error kind.
+    pub fn summary(&self) -> BatchSummary {
+        let mut summary = BatchSummary {
+            total: self.lines.len(),
+            ..BatchSummary::default()
+        };
+        for (_, error) in self.errors() {
+            summary.failed += 1;
+            *summary.errors.entry(error.kind()).or_default() += 1;
+        }
+        summary.parsed = summary.total - summary.failed;
+        summary
+    }
+}
+
+/// Parses files or streams with one SPAYD per line.
+///
+/// Surrounding whitespace, including the `\r` of CRLF line endings, is
+/// removed and blank lines are skipped. Each remaining line is parsed, which
+/// also validates it (see `Spayd::validate`). With the `parallel` feature the
+/// lines can be processed on several threads, keeping the results in line
+/// order.
+/// ```
+/// use spayd::BatchParser;
+///
+/// let text = "SPD*1.0*ACC:CZ5855000000001265098001*AM:100.00\r\n\
+///             \r\n\
+///             SPD*1.0*AM:100.00\r\n\
+///             not a spayd\r\n";
+/// let report = BatchParser::new().parse_lines(text.lines());
+///
+/// assert_eq!(report.payments().map(|(line, _)| line).collect::<Vec<_>>(), vec![1]);
+/// assert_eq!(report.errors().map(|(line, _)| line).collect::<Vec<_>>(), vec![3, 4]);
+/// assert_eq!(
+///     report.summary().to_string(),
+///     "3 lines: 1 parsed, 2 failed (ParseError: 1, RequiredFieldMissing: 1)"
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct BatchParser {
+    #[cfg(feature = "parallel")]
+    parallel: bool,
+}
+
+impl BatchParser {
+    /// A parser which processes the lines in order on the current thread.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse and validate the lines on rayon's thread pool.
+    #[cfg(feature = "parallel")]
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    fn parse_line(line: usize, text: Result<String, SpaydError>) -> BatchLine {
+        BatchLine {
+            line,
+            result: text.and_then(|text| text.parse()),
+        }
+    }
+
+    /// Parse lines from an iterator, numbering them from 1.
+    pub fn parse_lines<I, S>(&self, lines: I) -> BatchReport
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let lines = lines.into_iter().enumerate().filter_map(|(index, text)| {
+            let text = text.as_ref().trim();
+            (!text.is_empty()).then(|| (index + 1, Ok(text.to_owned())))
+        });
+        self.parse_numbered(lines)
+    }
+
+    /// Parse numbered lines which aren't blank, or the errors from reading
+    /// them.
+    fn parse_numbered<I>(&self, lines: I) -> BatchReport
+    where
+        I: Iterator<Item = (usize, Result<String, SpaydError>)>,
+    {
+        #[cfg(feature = "parallel")]
+        if self.parallel {
+            let lines: Vec<_> = lines.collect();
+            return BatchReport::new(
+                lines
+                    .into_par_iter()
+                    .map(|(line, text)| Self::parse_line(line, text))
+                    .collect(),
+            );
+        }
+
+        BatchReport::new(
+            lines
+                .map(|(line, text)| Self::parse_line(line, text))
+                .collect(),
+        )
+    }
+
+    /// Read and parse all lines of a reader. Fails with `Io` if reading
+    /// fails. A line which isn't valid UTF-8 gets a `FileFormat` error in
+    /// the report, like any other line which can't be parsed.
+    pub fn parse_reader<R: BufRead>(&self, mut reader: R) -> Result<BatchReport, SpaydError> {
+        let mut lines = Vec::new();
+        let mut buffer = Vec::new();
+        for line in 1.. {
+            buffer.clear();
+            let read = reader
+                .read_until(b'\n', &mut buffer)
+                .map_err(|e| SpaydError::Io(e.to_string()))?;
+            if read == 0 {
+                break;
+            }
+            match std::str::from_utf8(&buffer).map(str::trim) {
+                Ok("") => {}
+                Ok(text) => lines.push((line, Ok(text.to_owned()))),
+                Err(_) => lines.push((
+                    line,
+                    Err(SpaydError::FileFormat {
+                        format: FORMAT,
+                        line,
+                        reason: "not valid UTF-8".into(),
+                    }),
+                )),
+            }
+        }
+        Ok(self.parse_numbered(lines.into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BATCH: &str = "  SPD*1.0*ACC:CZ5855000000001265098001*AM:100.00  \r\n\
+                         \r\n\
+                         SPD*1.0*AM:100.00\r\n\
+                         SPD*1.0*ACC:CZ5855000000001265098001*AM:1%2\r\n\
+                         not a spayd\r\n\
+                         SPD*1.0*ACC:CZ5855000000001265098001*MSG:CRLF\r\n";
+
+    fn lines_with_errors(report: &BatchReport) -> Vec<(usize, &'static str)> {
+        report
+            .errors()
+            .map(|(line, error)| (line, error.kind()))
+            .collect()
+    }
+
+    #[test]
+    fn parse() {
+        let report = BatchParser::new().parse_lines(BATCH.lines());
+        assert_eq!(report.lines().len(), 5);
+        assert_eq!(
+            report.payments().map(|(line, _)| line).collect::<Vec<_>>(),
+            vec![1, 6]
+        );
+        assert_eq!(
+            report.payments().last().unwrap().1.field("MSG"),
+            Some("CRLF")
+        );
+        assert_eq!(
+            lines_with_errors(&report),
+            vec![
+                (3, "RequiredFieldMissing"),
+                (4, "MalformedEscape"),
+                (5, "ParseError")
+            ]
+        );
+    }
+
+    #[test]
+    fn summary() {
+        let summary = BatchParser::new().parse_lines(BATCH.lines()).summary();
+        assert_eq!(summary.total, 5);
+        assert_eq!(summary.parsed, 2);
+        assert_eq!(summary.failed, 3);
+        assert_eq!(summary.errors.get("ParseError"), Some(&1));
+        assert_eq!(
+            summary.to_string(),
+            "5 lines: 2 parsed, 3 failed (MalformedEscape: 1, ParseError: 1, RequiredFieldMissing: 1)"
+        );
+    }
+
+    #[test]
+    fn reader() {
+        let report = BatchParser::new().parse_reader(BATCH.as_bytes()).unwrap();
+        assert_eq!(report, BatchParser::new().parse_lines(BATCH.lines()));
+
+        let invalid: &[u8] =
+            b"SPD*1.0*ACC:CZ5855000000001265098001\n\n\xff\r\nSPD*1.0*ACC:CZ5855000000001265098001";
+        let report = BatchParser::new().parse_reader(invalid).unwrap();
+        assert_eq!(
+            report.payments().map(|(line, _)| line).collect::<Vec<_>>(),
+            vec![1, 4]
+        );
+        assert_eq!(
+            report.errors().collect::<Vec<_>>(),
+            vec![(
+                3,
+                &SpaydError::FileFormat {
+                    format: FORMAT,
+                    line: 3,
+                    reason: "not valid UTF-8".into()
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn empty_summary() {
+        let report = BatchParser::new().parse_lines(["", "  "]);
+        assert_eq!(report.summary(), BatchSummary::default());
+        assert_eq!(report.summary().to_string(), "0 lines: 0 parsed, 0 failed");
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel() {
+        let lines: Vec<String> = (0..1000)
+            .map(|i| match i % 3 {
+                0 => format!("SPD*1.0*ACC:CZ5855000000001265098001*AM:{}.00", i),
+                1 => "SPD*1.0*AM:1.00".into(),
+                _ => format!("broken {}", i),
+            })
+            .collect();
+        let sequential = BatchParser::new().parse_lines(&lines);
+        let parallel = BatchParser::new().with_parallel(true).parse_lines(&lines);
+
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel.summary().parsed, 334);
+        assert_eq!(parallel.summary().errors.len(), 2);
+    }
+}
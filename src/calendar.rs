@@ -0,0 +1,334 @@
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+use crate::Spayd;
+
+/// Public holiday calendars for the countries using SPAYD.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HolidayCalendar {
+    /// Public holidays in Czechia.
+    Czech,
+    /// Public holidays in Slovakia which are days off work.
+    Slovak,
+}
+
+/// How to move a date which isn't a banking day.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BusinessDayRule {
+    /// Move to the next banking day.
+    Following,
+    /// Move to the next banking day, unless it's in the next month. Then
+    /// move to the previous banking day instead.
+    ModifiedFollowing,
+    /// Move to the previous banking day.
+    Preceding,
+    /// Move to the previous banking day, unless it's in the previous month.
+    /// Then move to the next banking day instead.
+    ModifiedPreceding,
+}
+
+/// A holiday on the same day every year it's observed.
+struct FixedHoliday {
+    month: u32,
+    day: u32,
+    years: RangeInclusive<i32>,
+}
+
+/// A holiday observed in every year.
+const fn every_year(month: u32, day: u32) -> FixedHoliday {
+    FixedHoliday {
+        month,
+        day,
+        years: i32::MIN..=i32::MAX,
+    }
+}
+
+/// A holiday which stopped being a day off after `last_year`.
+const fn until(month: u32, day: u32, last_year: i32) -> FixedHoliday {
+    FixedHoliday {
+        month,
+        day,
+        years: i32::MIN..=last_year,
+    }
+}
+
+const CZECH_FIXED: &[FixedHoliday] = &[
+    every_year(1, 1),
+    every_year(5, 1),
+    every_year(5, 8),
+    every_year(7, 5),
+    every_year(7, 6),
+    every_year(9, 28),
+    every_year(10, 28),
+    every_year(11, 17),
+    every_year(12, 24),
+    every_year(12, 25),
+    every_year(12, 26),
+];
+
+/// Constitution Day (1 September) and the Day of the Fight for Freedom and
+/// Democracy (17 November) have been working days since the 2024
+/// consolidation amendment took effect in 2025.
+const SLOVAK_FIXED: &[FixedHoliday] = &[
+    every_year(1, 1),
+    every_year(1, 6),
+    every_year(5, 1),
+    every_year(5, 8),
+    every_year(7, 5),
+    every_year(8, 29),
+    until(9, 1, 2024),
+    every_year(9, 15),
+    every_year(11, 1),
+    until(11, 17, 2024),
+    every_year(12, 24),
+    every_year(12, 25),
+    every_year(12, 26),
+];
+
+/// Date of Easter Sunday in the Gregorian calendar, using the anonymous
+/// Gregorian algorithm (Meeus/Jones/Butcher).
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+impl HolidayCalendar {
+    /// Get the calendar for an ISO 3166 country code, e.g. the first two
+    /// letters of an IBAN.
+    pub fn for_country(code: &str) -> Option<Self> {
+        match code {
+            "CZ" => Some(Self::Czech),
+            "SK" => Some(Self::Slovak),
+            _ => None,
+        }
+    }
+
+    fn fixed_holidays(&self) -> &'static [FixedHoliday] {
+        match self {
+            Self::Czech => CZECH_FIXED,
+            Self::Slovak => SLOVAK_FIXED,
+        }
+    }
+
+    /// Good Friday has been a public holiday in Czechia since 2016.
+    fn has_good_friday(&self, year: i32) -> bool {
+        match self {
+            Self::Czech => year >= 2016,
+            Self::Slovak => true,
+        }
+    }
+
+    /// All public holidays in a year, in date order.
+    pub fn holidays(&self, year: i32) -> Vec<NaiveDate> {
+        let mut holidays: Vec<NaiveDate> = self
+            .fixed_holidays()
+            .iter()
+            .filter(|holiday| holiday.years.contains(&year))
+            .filter_map(|holiday| NaiveDate::from_ymd_opt(year, holiday.month, holiday.day))
+            .collect();
+
+        if let Some(easter) = easter_sunday(year) {
+            if self.has_good_friday(year) {
+                holidays.extend(easter.checked_sub_days(Days::new(2)));
+            }
+            holidays.extend(easter.checked_add_days(Days::new(1)));
+        }
+
+        holidays.sort();
+        holidays
+    }
+
+    /// Check if a date is a public holiday.
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays(date.year()).contains(&date)
+    }
+
+    /// Check if banks process payments on a date, i.e. it's not a weekend or
+    /// a public holiday.
+    pub fn is_banking_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(date)
+    }
+
+    /// Find the nearest banking day in one direction, starting from the date.
+    fn step_to_banking_day(&self, mut date: NaiveDate, forwards: bool) -> NaiveDate {
+        while !self.is_banking_day(date) {
+            let next = if forwards {
+                date.succ_opt()
+            } else {
+                date.pred_opt()
+            };
+            match next {
+                Some(next) => date = next,
+                None => break,
+            }
+        }
+        date
+    }
+
+    /// Move a date to a banking day using the given rule. Banking days are
+    /// returned unchanged.
+    pub fn adjust(&self, date: NaiveDate, rule: BusinessDayRule) -> NaiveDate {
+        let following = || self.step_to_banking_day(date, true);
+        let preceding = || self.step_to_banking_day(date, false);
+
+        match rule {
+            BusinessDayRule::Following => following(),
+            BusinessDayRule::Preceding => preceding(),
+            BusinessDayRule::ModifiedFollowing => {
+                let adjusted = following();
+                if adjusted.month() == date.month() {
+                    adjusted
+                } else {
+                    preceding()
+                }
+            }
+            BusinessDayRule::ModifiedPreceding => {
+                let adjusted = preceding();
+                if adjusted.month() == date.month() {
+                    adjusted
+                } else {
+                    following()
+                }
+            }
+        }
+    }
+}
+
+impl Spayd {
+    /// Set the due date, moving it to a banking day in the given calendar.
+    /// Returns the date which was set.
+    pub fn set_due_date_adjusted(
+        &mut self,
+        date: &NaiveDate,
+        rule: BusinessDayRule,
+        calendar: HolidayCalendar,
+    ) -> NaiveDate {
+        let adjusted = calendar.adjust(*date, rule);
+        self.set_due_date(&adjusted);
+        adjusted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn easter() {
+        assert_eq!(easter_sunday(2024), Some(date(2024, 3, 31)));
+        assert_eq!(easter_sunday(2025), Some(date(2025, 4, 20)));
+        assert_eq!(easter_sunday(2026), Some(date(2026, 4, 5)));
+        assert_eq!(easter_sunday(2038), Some(date(2038, 4, 25)));
+    }
+
+    #[test]
+    fn czech_holidays() {
+        let czech = HolidayCalendar::Czech;
+        assert!(czech.is_holiday(date(2026, 10, 28)));
+        assert!(czech.is_holiday(date(2026, 4, 3)));
+        assert!(czech.is_holiday(date(2026, 4, 6)));
+        assert!(!czech.is_holiday(date(2026, 1, 6)));
+        assert!(!czech.is_holiday(date(2015, 4, 3)));
+        assert_eq!(czech.holidays(2026).len(), 13);
+    }
+
+    #[test]
+    fn slovak_holidays() {
+        let slovak = HolidayCalendar::Slovak;
+        assert!(slovak.is_holiday(date(2026, 1, 6)));
+        assert!(slovak.is_holiday(date(2026, 8, 29)));
+        assert!(!slovak.is_holiday(date(2026, 10, 28)));
+        assert_eq!(slovak.holidays(2026).len(), 13);
+        assert_eq!(HolidayCalendar::for_country("SK"), Some(slovak));
+        assert_eq!(HolidayCalendar::for_country("DE"), None);
+    }
+
+    #[test]
+    fn slovak_consolidation_amendment() {
+        let slovak = HolidayCalendar::Slovak;
+        assert!(slovak.is_holiday(date(2024, 9, 1)));
+        assert!(slovak.is_holiday(date(2024, 11, 17)));
+        assert_eq!(slovak.holidays(2024).len(), 15);
+
+        assert!(!slovak.is_holiday(date(2025, 9, 1)));
+        assert!(!slovak.is_holiday(date(2025, 11, 17)));
+        assert!(slovak.is_banking_day(date(2025, 9, 1)));
+        assert!(slovak.is_banking_day(date(2026, 11, 17)));
+    }
+
+    #[test]
+    fn banking_days() {
+        let czech = HolidayCalendar::Czech;
+        assert!(czech.is_banking_day(date(2026, 10, 27)));
+        assert!(!czech.is_banking_day(date(2026, 10, 28)));
+        assert!(!czech.is_banking_day(date(2026, 10, 31)));
+    }
+
+    #[test]
+    fn adjustment_rules() {
+        let czech = HolidayCalendar::Czech;
+        let christmas = date(2026, 12, 24);
+        assert_eq!(
+            czech.adjust(christmas, BusinessDayRule::Following),
+            date(2026, 12, 28)
+        );
+        assert_eq!(
+            czech.adjust(christmas, BusinessDayRule::Preceding),
+            date(2026, 12, 23)
+        );
+
+        // Saturday 31 October 2026 would move into November
+        let month_end = date(2026, 10, 31);
+        assert_eq!(
+            czech.adjust(month_end, BusinessDayRule::Following),
+            date(2026, 11, 2)
+        );
+        assert_eq!(
+            czech.adjust(month_end, BusinessDayRule::ModifiedFollowing),
+            date(2026, 10, 30)
+        );
+
+        // Friday 1 May 2026 is a holiday, the previous banking day is in April
+        let may_day = date(2026, 5, 1);
+        assert_eq!(
+            czech.adjust(may_day, BusinessDayRule::ModifiedPreceding),
+            date(2026, 5, 4)
+        );
+
+        let banking_day = date(2026, 10, 27);
+        assert_eq!(
+            czech.adjust(banking_day, BusinessDayRule::Following),
+            banking_day
+        );
+    }
+
+    #[test]
+    fn set_adjusted_due_date() {
+        let mut spayd = Spayd::empty_v1_0();
+        let due = spayd.set_due_date_adjusted(
+            &date(2026, 10, 28),
+            BusinessDayRule::Following,
+            HolidayCalendar::Czech,
+        );
+        assert_eq!(due, date(2026, 10, 29));
+        assert_eq!(spayd.field("DT"), Some("20261029"));
+    }
+}
@@ -0,0 +1,480 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::amount::parse_minor_units;
+use crate::xml::{currency_attribute, line_at, parse_date, path_ends_with};
+use crate::{Direction, SpaydError, Transaction};
+
+const FORMAT: &str = "camt XML";
+
+/// End-to-end ID used when the payer didn't supply one.
+const NOT_PROVIDED: &str = "NOTPROVIDED";
+
+/// The kind of ISO 20022 cash management message.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CamtMessage {
+    /// camt.053 bank-to-customer statement.
+    Statement,
+    /// camt.054 bank-to-customer debit/credit notification.
+    Notification,
+}
+
+/// Transactions read from an ISO 20022 camt.053 statement or camt.054
+/// notification.
+///
+/// Each entry (`Ntry`) gives one transaction, or one per transaction detail
+/// (`TxDtls`) for batch bookings. Czech symbols are taken from structured
+/// creditor references typed as VS, SS or KS, or found in the end-to-end ID,
+/// creditor reference and unstructured remittance information, e.g.
+/// "/VS/1001/SS/22/KS/0308".
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CamtStatement {
+    message: CamtMessage,
+    transactions: Vec<Transaction>,
+}
+
+/// A transaction detail (`TxDtls`) being read.
+#[derive(Default)]
+struct Detail {
+    transaction: Transaction,
+    amount: Option<(u64, String)>,
+    direction: Option<Direction>,
+    debtor_account: Option<String>,
+    creditor_account: Option<String>,
+    reference_type: Option<String>,
+    texts: Vec<String>,
+}
+
+/// An entry (`Ntry`) being read.
+#[derive(Default)]
+struct Entry {
+    amount: Option<(u64, String)>,
+    direction: Option<Direction>,
+    booking_date: Option<NaiveDate>,
+    value_date: Option<NaiveDate>,
+    id: Option<String>,
+    details: Vec<Detail>,
+}
+
+fn parse_direction(text: &str) -> Option<Direction> {
+    match text {
+        "CRDT" => Some(Direction::Credit),
+        "DBIT" => Some(Direction::Debit),
+        _ => None,
+    }
+}
+
+fn append(target: &mut Option<String>, text: &str) {
+    match target {
+        Some(existing) => {
+            existing.push(' ');
+            existing.push_str(text);
+        }
+        None => *target = Some(text.into()),
+    }
+}
+
+impl Entry {
+    fn into_transactions(self, account: &Option<String>) -> Result<Vec<Transaction>, String> {
+        let date = self
+            .booking_date
+            .or(self.value_date)
+            .ok_or("entry without a booking date")?;
+        let entry_direction = self.direction.ok_or("entry without CdtDbtInd")?;
+        let entry_amount = self.amount.clone();
+        let single = self.details.len() <= 1;
+
+        let details = if self.details.is_empty() {
+            vec![Detail::default()]
+        } else {
+            self.details
+        };
+
+        details
+            .into_iter()
+            .map(|detail| {
+                let mut transaction = detail.transaction;
+                let (amount, currency) = match (detail.amount, &entry_amount) {
+                    (Some(amount), _) => amount,
+                    (None, Some(amount)) if single => amount.clone(),
+                    _ => return Err("entry without an amount".to_owned()),
+                };
+                transaction.amount = amount;
+                transaction.currency = currency;
+                transaction.date = date;
+                transaction.direction = detail.direction.unwrap_or(entry_direction);
+                transaction.account = account.clone();
+                transaction.counterparty = match transaction.direction {
+                    Direction::Credit => detail.debtor_account,
+                    Direction::Debit => detail.creditor_account,
+                };
+                if transaction.id.is_none() {
+                    transaction.id = self.id.clone();
+                }
+                for text in &detail.texts {
+                    transaction.fill_symbols_from(text);
+                }
+                Ok(transaction)
+            })
+            .collect()
+    }
+}
+
+impl CamtStatement {
+    /// Read a camt.053 or camt.054 file from disk.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SpaydError> {
+        std::fs::read_to_string(path)
+            .map_err(|e| SpaydError::Io(e.to_string()))?
+            .parse()
+    }
+
+    /// Whether the document is a statement or a notification.
+    pub fn message(&self) -> CamtMessage {
+        self.message
+    }
+
+    /// The transactions in the order they appear.
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// Take the transactions.
+    pub fn into_transactions(self) -> Vec<Transaction> {
+        self.transactions
+    }
+}
+
+impl FromStr for CamtStatement {
+    type Err = SpaydError;
+
+    fn from_str(xml: &str) -> Result<Self, Self::Err> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+
+        let mut message = None;
+        let mut path: Vec<String> = Vec::new();
+        let mut currency: Option<String> = None;
+        let mut account: Option<String> = None;
+        let mut entry: Option<Entry> = None;
+        let mut transactions = Vec::new();
+
+        loop {
+            let position = reader.buffer_position() as usize;
+            let error = |reason: &str| SpaydError::FileFormat {
+                format: FORMAT,
+                line: line_at(xml, position),
+                reason: reason.into(),
+            };
+
+            let event = reader.read_event().map_err(|e| error(&e.to_string()))?;
+            match event {
+                Event::Start(start) => {
+                    let name = String::from_utf8_lossy(start.local_name().as_ref()).into_owned();
+                    match name.as_str() {
+                        "BkToCstmrStmt" => message = Some(CamtMessage::Statement),
+                        "BkToCstmrDbtCdtNtfctn" => message = Some(CamtMessage::Notification),
+                        "Stmt" | "Ntfctn" => account = None,
+                        "Ntry" => entry = Some(Entry::default()),
+                        "TxDtls" => {
+                            if let Some(entry) = &mut entry {
+                                entry.details.push(Detail::default());
+                            }
+                        }
+                        "Amt" => currency = currency_attribute(&start),
+                        _ => {}
+                    }
+                    path.push(name);
+                }
+                Event::End(_) => {
+                    if path.last().map(String::as_str) == Some("Ntry") {
+                        if let Some(finished) = entry.take() {
+                            transactions.extend(
+                                finished
+                                    .into_transactions(&account)
+                                    .map_err(|e| error(&e))?,
+                            );
+                        }
+                    }
+                    path.pop();
+                }
+                Event::Text(text) => {
+                    let text = text.unescape().map_err(|e| error(&e.to_string()))?;
+                    read_text(&path, text.trim(), &currency, &mut account, &mut entry)
+                        .map_err(|reason| error(&reason))?;
+                }
+                Event::CData(data) => {
+                    let text = String::from_utf8_lossy(&data).into_owned();
+                    read_text(&path, text.trim(), &currency, &mut account, &mut entry)
+                        .map_err(|reason| error(&reason))?;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        let message = message.ok_or(SpaydError::FileFormat {
+            format: FORMAT,
+            line: 1,
+            reason: "not a camt.053 or camt.054 document".into(),
+        })?;
+        Ok(Self {
+            message,
+            transactions,
+        })
+    }
+}
+
+/// Store the text of an element according to where it is in the document.
+fn read_text(
+    path: &[String],
+    text: &str,
+    currency: &Option<String>,
+    account: &mut Option<String>,
+    entry: &mut Option<Entry>,
+) -> Result<(), String> {
+    let parent = |depth: usize| path.len().checked_sub(depth + 1).map(|i| path[i].as_str());
+    let is_statement = |name: Option<&str>| matches!(name, Some("Stmt" | "Ntfctn"));
+
+    if path_ends_with(path, &["Acct", "Id", "IBAN"]) && is_statement(parent(3)) {
+        *account = Some(text.into());
+        return Ok(());
+    }
+    if path_ends_with(path, &["Acct", "Id", "Othr", "Id"]) && is_statement(parent(4)) {
+        *account = Some(text.into());
+        return Ok(());
+    }
+
+    let Some(entry) = entry else {
+        return Ok(());
+    };
+    let amount = || -> Result<(u64, String), String> {
+        let amount = parse_minor_units(text).ok_or_else(|| format!("invalid amount '{}'", text))?;
+        let currency = currency.clone().ok_or("amount without a currency")?;
+        Ok((amount, currency))
+    };
+
+    let Some(detail) = entry
+        .details
+        .last_mut()
+        .filter(|_| path.iter().any(|n| n == "TxDtls"))
+    else {
+        if path_ends_with(path, &["Ntry", "Amt"]) {
+            entry.amount = Some(amount()?);
+        } else if path_ends_with(path, &["Ntry", "CdtDbtInd"]) {
+            entry.direction = Some(parse_direction(text).ok_or("invalid CdtDbtInd")?);
+        } else if path_ends_with(path, &["Ntry", "BookgDt", "Dt"])
+            || path_ends_with(path, &["Ntry", "BookgDt", "DtTm"])
+        {
+            entry.booking_date = Some(parse_date(text).ok_or("invalid booking date")?);
+        } else if path_ends_with(path, &["Ntry", "ValDt", "Dt"])
+            || path_ends_with(path, &["Ntry", "ValDt", "DtTm"])
+        {
+            entry.value_date = parse_date(text);
+        } else if path_ends_with(path, &["Ntry", "AcctSvcrRef"]) {
+            entry.id = Some(text.into());
+        }
+        return Ok(());
+    };
+
+    let transaction = &mut detail.transaction;
+    if path_ends_with(path, &["TxDtls", "Amt"]) || path_ends_with(path, &["TxAmt", "Amt"]) {
+        detail.amount = Some(amount()?);
+    } else if path_ends_with(path, &["TxDtls", "CdtDbtInd"]) {
+        detail.direction = parse_direction(text);
+    } else if path_ends_with(path, &["Refs", "AcctSvcrRef"]) {
+        transaction.id = Some(text.into());
+    } else if path_ends_with(path, &["Refs", "EndToEndId"]) {
+        if text != NOT_PROVIDED {
+            if transaction.reference.is_none() {
+                transaction.reference = Some(text.into());
+            }
+            detail.texts.push(text.into());
+        }
+    } else if path_ends_with(path, &["DbtrAcct", "Id", "IBAN"])
+        || path_ends_with(path, &["DbtrAcct", "Id", "Othr", "Id"])
+    {
+        detail.debtor_account = Some(text.into());
+    } else if path_ends_with(path, &["CdtrAcct", "Id", "IBAN"])
+        || path_ends_with(path, &["CdtrAcct", "Id", "Othr", "Id"])
+    {
+        detail.creditor_account = Some(text.into());
+    } else if path_ends_with(path, &["RmtInf", "Ustrd"]) {
+        append(&mut transaction.message, text);
+        detail.texts.push(text.into());
+    } else if path_ends_with(path, &["CdtrRefInf", "Tp", "CdOrPrtry", "Cd"])
+        || path_ends_with(path, &["CdtrRefInf", "Tp", "CdOrPrtry", "Prtry"])
+    {
+        detail.reference_type = Some(text.to_ascii_uppercase());
+    } else if path_ends_with(path, &["CdtrRefInf", "Ref"]) {
+        match detail.reference_type.take().as_deref() {
+            Some("VS") => transaction.variable_symbol = Some(text.into()),
+            Some("SS") => transaction.specific_symbol = Some(text.into()),
+            Some("KS") => transaction.constant_symbol = Some(text.into()),
+            _ => {
+                transaction.reference = Some(text.into());
+                detail.texts.push(text.into());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATEMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+  <BkToCstmrStmt>
+    <GrpHdr><MsgId>STMT-1</MsgId></GrpHdr>
+    <Stmt>
+      <Id>1</Id>
+      <Acct><Id><IBAN>CZ5855000000001265098001</IBAN></Id></Acct>
+      <Ntry>
+        <Amt Ccy="CZK">500.00</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <BookgDt><Dt>2026-10-16</Dt></BookgDt>
+        <AcctSvcrRef>TX-1</AcctSvcrRef>
+        <NtryDtls><TxDtls>
+          <Refs><EndToEndId>VS1001/SS22/KS0308</EndToEndId></Refs>
+          <RltdPties>
+            <DbtrAcct><Id><IBAN>CZ6508000000192000145399</IBAN></Id></DbtrAcct>
+            <CdtrAcct><Id><IBAN>CZ5855000000001265098001</IBAN></Id></CdtrAcct>
+          </RltdPties>
+          <RmtInf><Ustrd>Invoice 2026-00123</Ustrd></RmtInf>
+        </TxDtls></NtryDtls>
+      </Ntry>
+      <Ntry>
+        <Amt Ccy="CZK">300.00</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <BookgDt><DtTm>2026-10-17T09:30:00+02:00</DtTm></BookgDt>
+        <NtryDtls>
+          <TxDtls>
+            <Refs><EndToEndId>NOTPROVIDED</EndToEndId></Refs>
+            <AmtDtls><TxAmt><Amt Ccy="CZK">100.00</Amt></TxAmt></AmtDtls>
+            <RmtInf><Strd><CdtrRefInf>
+              <Tp><CdOrPrtry><Prtry>VS</Prtry></CdOrPrtry></Tp>
+              <Ref>1002</Ref>
+            </CdtrRefInf></Strd></RmtInf>
+          </TxDtls>
+          <TxDtls>
+            <AmtDtls><TxAmt><Amt Ccy="CZK">200.00</Amt></TxAmt></AmtDtls>
+            <RmtInf><Strd><CdtrRefInf>
+              <Tp><CdOrPrtry><Cd>SCOR</Cd></CdOrPrtry></Tp>
+              <Ref>RF18539007547034</Ref>
+            </CdtrRefInf></Strd></RmtInf>
+          </TxDtls>
+        </NtryDtls>
+      </Ntry>
+      <Ntry>
+        <Amt Ccy="EUR">12.5</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <BookgDt><Dt>2026-10-18</Dt></BookgDt>
+        <NtryDtls><TxDtls>
+          <RltdPties><CdtrAcct><Id><IBAN>DE89370400440532013000</IBAN></Id></CdtrAcct></RltdPties>
+          <RmtInf><Ustrd>Fee /VS/77</Ustrd></RmtInf>
+        </TxDtls></NtryDtls>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>
+"#;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn parse_statement() {
+        let statement: CamtStatement = STATEMENT.parse().unwrap();
+        assert_eq!(statement.message(), CamtMessage::Statement);
+
+        let transactions = statement.transactions();
+        assert_eq!(transactions.len(), 4);
+        assert_eq!(
+            transactions[0],
+            Transaction {
+                id: Some("TX-1".into()),
+                direction: Direction::Credit,
+                amount: 50_000,
+                currency: "CZK".into(),
+                date: date(16),
+                account: Some("CZ5855000000001265098001".into()),
+                counterparty: Some("CZ6508000000192000145399".into()),
+                variable_symbol: Some("1001".into()),
+                specific_symbol: Some("22".into()),
+                constant_symbol: Some("0308".into()),
+                reference: Some("VS1001/SS22/KS0308".into()),
+                message: Some("Invoice 2026-00123".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn batch_entry() {
+        let statement: CamtStatement = STATEMENT.parse().unwrap();
+        let transactions = statement.transactions();
+
+        assert_eq!(transactions[1].amount, 10_000);
+        assert_eq!(transactions[1].date, date(17));
+        assert_eq!(transactions[1].variable_symbol.as_deref(), Some("1002"));
+        assert_eq!(transactions[1].reference, None);
+        assert_eq!(transactions[2].amount, 20_000);
+        assert_eq!(
+            transactions[2].reference.as_deref(),
+            Some("RF18539007547034")
+        );
+    }
+
+    #[test]
+    fn large_amount() {
+        let xml = STATEMENT.replace(">500.00<", ">12500000.00<");
+        let statement: CamtStatement = xml.parse().unwrap();
+        assert_eq!(statement.transactions()[0].amount, 1_250_000_000);
+    }
+
+    #[test]
+    fn debit_entry() {
+        let statement: CamtStatement = STATEMENT.parse().unwrap();
+        let fee = &statement.transactions()[3];
+
+        assert_eq!(fee.direction, Direction::Debit);
+        assert_eq!(fee.currency, "EUR");
+        assert_eq!(fee.amount, 1_250);
+        assert_eq!(fee.counterparty.as_deref(), Some("DE89370400440532013000"));
+        assert_eq!(fee.variable_symbol.as_deref(), Some("77"));
+    }
+
+    #[test]
+    fn notification() {
+        let xml = STATEMENT
+            .replace("BkToCstmrStmt", "BkToCstmrDbtCdtNtfctn")
+            .replace("<Stmt>", "<Ntfctn>")
+            .replace("</Stmt>", "</Ntfctn>");
+        let notification: CamtStatement = xml.parse().unwrap();
+        assert_eq!(notification.message(), CamtMessage::Notification);
+        assert_eq!(notification.transactions().len(), 4);
+    }
+
+    #[test]
+    fn invalid_documents() {
+        assert!(matches!(
+            "<Document/>".parse::<CamtStatement>(),
+            Err(SpaydError::FileFormat { line: 1, .. })
+        ));
+
+        let bad_amount = STATEMENT.replace("500.00", "five hundred");
+        assert_eq!(
+            bad_amount.parse::<CamtStatement>(),
+            Err(SpaydError::FileFormat {
+                format: FORMAT,
+                line: 9,
+                reason: "invalid amount 'five hundred'".into()
+            })
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use iso_currency::Currency;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::{fields, round_amount, RoundingMode, Spayd, SpaydError};
+
+const FORMAT: &str = "CNB exchange rates";
+const COLUMNS: &str = "země|měna|množství|kód|kurz";
+
+/// Number of decimal places in the rates published by the CNB.
+const RATE_DECIMALS: u32 = 3;
+
+/// The exchange rate of one currency against CZK.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExchangeRate {
+    /// Number of units of the currency which the rate is given for, e.g. 100
+    /// for JPY.
+    pub quantity: u32,
+    /// Price of `quantity` units of the currency in CZK.
+    pub rate: Decimal,
+}
+
+impl ExchangeRate {
+    /// The price of one unit of the currency in CZK.
+    pub fn per_unit(&self) -> Decimal {
+        self.rate / Decimal::from(self.quantity)
+    }
+}
+
+/// Daily exchange rates published by the Czech National Bank in the
+/// `denni_kurz.txt` text format:
+/// ```text
+/// 16.10.2026 #200
+/// země|měna|množství|kód|kurz
+/// EMU|euro|1|EUR|24,335
+/// Japonsko|jen|100|JPY|15,483
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ExchangeRates {
+    date: NaiveDate,
+    serial: u32,
+    rates: BTreeMap<&'static str, (Currency, ExchangeRate)>,
+}
+
+fn format_error(line: usize, reason: &str) -> SpaydError {
+    SpaydError::FileFormat {
+        format: FORMAT,
+        line,
+        reason: reason.into(),
+    }
+}
+
+impl ExchangeRates {
+    /// Read a rate file from disk.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SpaydError> {
+        std::fs::read_to_string(path)
+            .map_err(|e| SpaydError::Io(e.to_string()))?
+            .parse()
+    }
+
+    /// The date the rates were declared for.
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// The serial number of the declaration in its year.
+    pub fn serial(&self) -> u32 {
+        self.serial
+    }
+
+    /// Get the rate for a currency. CZK isn't listed in the file and always
+    /// has a rate of 1.
+    pub fn rate(&self, currency: Currency) -> Option<ExchangeRate> {
+        if currency == Currency::CZK {
+            return Some(ExchangeRate {
+                quantity: 1,
+                rate: Decimal::ONE,
+            });
+        }
+        self.rates.get(currency.code()).map(|(_, rate)| *rate)
+    }
+
+    /// Iterate over the listed currencies and their rates.
+    pub fn iter(&self) -> impl Iterator<Item = (Currency, ExchangeRate)> + '_ {
+        self.rates.values().copied()
+    }
+
+    /// Convert an amount between two currencies through CZK, without rounding.
+    /// Fails with `ConvertError` if either currency has no rate or the
+    /// result is too large for a `Decimal`.
+    pub fn convert(
+        &self,
+        amount: &Decimal,
+        from: Currency,
+        to: Currency,
+    ) -> Result<Decimal, SpaydError> {
+        let rate = |currency: Currency| {
+            self.rate(currency)
+                .ok_or(SpaydError::ConvertError(currency.code().into()))
+        };
+        let (from_rate, to_rate) = (rate(from)?, rate(to)?);
+        amount
+            .checked_mul(from_rate.per_unit())
+            .and_then(|czk| czk.checked_div(to_rate.per_unit()))
+            .ok_or(SpaydError::ConvertError(amount.to_string()))
+    }
+}
+
+impl FromStr for ExchangeRates {
+    type Err = SpaydError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()));
+
+        let (_, header) = lines.next().ok_or(format_error(1, "empty file"))?;
+        let (date, serial) = header
+            .split_once(" #")
+            .ok_or(format_error(1, "expected 'date #serial'"))?;
+        let date =
+            NaiveDate::parse_from_str(date, "%d.%m.%Y").or(Err(format_error(1, "invalid date")))?;
+        let serial = serial
+            .parse()
+            .or(Err(format_error(1, "invalid serial number")))?;
+
+        match lines.next() {
+            Some((_, COLUMNS)) => {}
+            _ => return Err(format_error(2, "expected column names")),
+        }
+
+        let mut rates = BTreeMap::new();
+        for (number, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let columns: Vec<&str> = line.split('|').collect();
+            let [_, _, quantity, code, rate] = columns[..] else {
+                return Err(format_error(number, "expected 5 columns"));
+            };
+            let currency =
+                Currency::from_code(code).ok_or(format_error(number, "unknown currency"))?;
+            let quantity = quantity
+                .parse()
+                .ok()
+                .filter(|q| *q > 0)
+                .ok_or(format_error(number, "invalid quantity"))?;
+            let rate = Decimal::from_str(&rate.replace(',', "."))
+                .ok()
+                .filter(|r| r.is_sign_positive() && !r.is_zero())
+                .ok_or(format_error(number, "invalid rate"))?;
+            rates.insert(currency.code(), (currency, ExchangeRate { quantity, rate }));
+        }
+
+        Ok(Self {
+            date,
+            serial,
+            rates,
+        })
+    }
+}
+
+/// Details of a currency conversion made by `Spayd::convert_currency`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CurrencyConversion {
+    /// The original currency.
+    pub from: Currency,
+    /// The new currency.
+    pub to: Currency,
+    /// The original amount.
+    pub original_amount: Decimal,
+    /// The converted and rounded amount.
+    pub amount: Decimal,
+    /// The price of one unit of the original currency in the new currency,
+    /// rounded half-up to the 3 decimal places the CNB publishes.
+    pub rate: Decimal,
+    /// The date of the exchange rates used.
+    pub rates_date: NaiveDate,
+}
+
+impl Display for CurrencyConversion {
+    /// A note describing the rate used, e.g.
+    /// "CNB rate 16.10.2026: 1 EUR = 24.335 CZK".
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CNB rate {}: 1 {} = {} {}",
+            self.rates_date.format("%d.%m.%Y"),
+            self.from.code(),
+            self.rate.normalize(),
+            self.to.code()
+        )
+    }
+}
+
+impl Spayd {
+    /// Convert the amount into another currency using CNB exchange rates,
+    /// rewriting the AM and CC fields.
+    ///
+    /// The amount is converted through CZK and rounded half-up to the number
+    /// of decimal places allowed for the new currency (see `currency_decimals`).
+    /// A note of the rate used, the `Display` output of the returned
+    /// conversion, is written to the `X-FX` field (`fields::EXCHANGE_RATE`).
+    /// The SPAYD isn't changed if the conversion fails.
+    pub fn convert_currency(
+        &mut self,
+        rates: &ExchangeRates,
+        target: Currency,
+    ) -> Result<CurrencyConversion, SpaydError> {
+        let from = self.currency()?;
+        let original_amount = self.amount()?;
+
+        let rate = rates
+            .convert(&Decimal::ONE, from, target)?
+            .round_dp_with_strategy(RATE_DECIMALS, RoundingStrategy::MidpointAwayFromZero);
+        let converted = rates.convert(&original_amount, from, target)?;
+        let amount = round_amount(&converted, target, RoundingMode::HalfUp);
+        let conversion = CurrencyConversion {
+            from,
+            to: target,
+            original_amount,
+            amount,
+            rate,
+            rates_date: rates.date(),
+        };
+
+        let mut converted_spayd = self.clone();
+        converted_spayd.set_currency(target);
+        converted_spayd.set_amount_exact(&amount)?;
+        converted_spayd.set_field(fields::EXCHANGE_RATE, conversion);
+        *self = converted_spayd;
+
+        Ok(conversion)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATES: &str = "16.10.2026 #200
+země|měna|množství|kód|kurz
+Austrálie|dolar|1|AUD|14,776
+EMU|euro|1|EUR|24,335
+Japonsko|jen|100|JPY|15,483
+";
+
+    #[test]
+    fn parse() {
+        let rates: ExchangeRates = RATES.parse().unwrap();
+        assert_eq!(rates.date(), NaiveDate::from_ymd_opt(2026, 10, 16).unwrap());
+        assert_eq!(rates.serial(), 200);
+        assert_eq!(rates.iter().count(), 3);
+        assert_eq!(
+            rates.rate(Currency::JPY),
+            Some(ExchangeRate {
+                quantity: 100,
+                rate: Decimal::new(15483, 3)
+            })
+        );
+        assert_eq!(rates.rate(Currency::USD), None);
+        assert_eq!(rates.rate(Currency::CZK).unwrap().rate, Decimal::ONE);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "".parse::<ExchangeRates>(),
+            Err(format_error(1, "empty file"))
+        );
+        assert_eq!(
+            "16.10.2026 #200\nfoo".parse::<ExchangeRates>(),
+            Err(format_error(2, "expected column names"))
+        );
+        assert_eq!(
+            format!("{RATES}EMU|euro|1|EUR").parse::<ExchangeRates>(),
+            Err(format_error(6, "expected 5 columns"))
+        );
+        assert_eq!(
+            format!("{RATES}EMU|euro|1|EUR|abc").parse::<ExchangeRates>(),
+            Err(format_error(6, "invalid rate"))
+        );
+    }
+
+    #[test]
+    fn from_file() {
+        let path = std::env::temp_dir().join(format!("spayd-cnb-{}.txt", std::process::id()));
+        std::fs::write(&path, RATES).unwrap();
+        let rates = ExchangeRates::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(rates, RATES.parse());
+        assert!(matches!(
+            ExchangeRates::from_file("/nonexistent/denni_kurz.txt"),
+            Err(SpaydError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn convert_eur_to_czk() {
+        let rates: ExchangeRates = RATES.parse().unwrap();
+        let mut spayd = Spayd::new_v1_0(vec![
+            ("ACC", "CZ5855000000001265098001"),
+            ("AM", "100.00"),
+            ("CC", "EUR"),
+        ]);
+
+        let conversion = spayd.convert_currency(&rates, Currency::CZK).unwrap();
+        assert_eq!(spayd.field("AM"), Some("2433.50"));
+        assert_eq!(spayd.field("CC"), Some("CZK"));
+        assert_eq!(conversion.amount, Decimal::new(243350, 2));
+        assert_eq!(
+            conversion.to_string(),
+            "CNB rate 16.10.2026: 1 EUR = 24.335 CZK"
+        );
+        assert_eq!(
+            spayd.field("X-FX"),
+            Some("CNB rate 16.10.2026: 1 EUR = 24.335 CZK")
+        );
+        assert_eq!(spayd.validate(), Ok(()));
+    }
+
+    #[test]
+    fn convert_through_czk() {
+        let rates: ExchangeRates = RATES.parse().unwrap();
+        let mut spayd = Spayd::new_v1_0(vec![
+            ("ACC", "CZ5855000000001265098001"),
+            ("AM", "10.00"),
+            ("CC", "EUR"),
+        ]);
+
+        let conversion = spayd.convert_currency(&rates, Currency::JPY).unwrap();
+        // 10 EUR = 243.35 CZK = 1571.72... JPY, rounded to whole yen
+        assert_eq!(spayd.field("AM"), Some("1572"));
+        assert_eq!(spayd.field("CC"), Some("JPY"));
+        // 24.335 / 0.15483 = 157.172...
+        assert_eq!(conversion.rate, Decimal::new(157172, 3));
+        assert_eq!(
+            spayd.field("X-FX"),
+            Some("CNB rate 16.10.2026: 1 EUR = 157.172 JPY")
+        );
+    }
+
+    #[test]
+    fn unknown_rate() {
+        let rates: ExchangeRates = RATES.parse().unwrap();
+        let mut spayd = Spayd::new_v1_0(vec![
+            ("ACC", "CZ5855000000001265098001"),
+            ("AM", "10.00"),
+            ("CC", "USD"),
+        ]);
+        let original = spayd.clone();
+
+        assert_eq!(
+            spayd.convert_currency(&rates, Currency::CZK),
+            Err(SpaydError::ConvertError("USD".into()))
+        );
+        assert_eq!(spayd, original);
+    }
+
+    #[test]
+    fn overflow() {
+        let rates: ExchangeRates = RATES.parse().unwrap();
+        assert!(matches!(
+            rates.convert(&Decimal::MAX, Currency::EUR, Currency::CZK),
+            Err(SpaydError::ConvertError(_))
+        ));
+        assert_eq!(
+            rates.convert(&Decimal::ONE, Currency::USD, Currency::CZK),
+            Err(SpaydError::ConvertError("USD".into()))
+        );
+
+        let mut spayd = Spayd::new_v1_0(vec![
+            ("ACC", "CZ5855000000001265098001"),
+            ("AM", "79228162514264337593543950335"),
+            ("CC", "EUR"),
+        ]);
+        let original = spayd.clone();
+        assert!(spayd.convert_currency(&rates, Currency::CZK).is_err());
+        assert_eq!(spayd, original);
+    }
+}
@@ -11,12 +11,18 @@ use iso_currency::Currency;
 #[cfg(feature = "rust_decimal")]
 use rust_decimal::Decimal;
 
-#[cfg(feature = "chrono")]
+#[cfg(feature = "time")]
+use time::{format_description::BorrowedFormatItem, macros::format_description};
+
+#[cfg(any(feature = "chrono", feature = "jiff"))]
 const SPAYD_DATE_FMT: &str = "%Y%m%d";
 
+#[cfg(feature = "time")]
+const SPAYD_TIME_FMT: &[BorrowedFormatItem<'static>] = format_description!("[year][month][day]");
+
 impl Spayd {
     /// Get the value of a field converted using the convert function
-    fn field_converted<T, E, F>(&self, field: &str, convert: F) -> Result<T, SpaydError>
+    pub(crate) fn field_converted<T, E, F>(&self, field: &str, convert: F) -> Result<T, SpaydError>
     where
         F: FnOnce(&str) -> Result<T, E>,
     {
@@ -27,8 +33,8 @@ impl Spayd {
         }
     }
 
-    /// Set the due date from a Chrono NaiveDate
-    fn set_field_converted<F, T, U>(&mut self, field: &'static str, value: T, convert: F)
+    /// Set the value of a field converted using the convert function
+    pub(crate) fn set_field_converted<F, T, U>(&mut self, field: &'static str, value: T, convert: F)
     where
         F: FnOnce(T) -> U,
         U: ToString,
@@ -89,13 +95,48 @@ impl Spayd {
         })
     }
 
+    /// Get the due date as a `time` Date
+    #[cfg(feature = "time")]
+    pub fn due_date_time(&self) -> Result<time::Date, SpaydError> {
+        self.field_converted(fields::DUE_DATE, |text| {
+            time::Date::parse(text, SPAYD_TIME_FMT)
+        })
+    }
+
+    /// Set the due date from a `time` Date
+    #[cfg(feature = "time")]
+    pub fn set_due_date_time(&mut self, date: &time::Date) {
+        self.set_field_converted(fields::DUE_DATE, date, |date| {
+            date.format(SPAYD_TIME_FMT)
+                .expect("a date has every component of the format")
+        })
+    }
+
+    /// Get the due date as a Jiff civil Date
+    #[cfg(feature = "jiff")]
+    pub fn due_date_jiff(&self) -> Result<jiff::civil::Date, SpaydError> {
+        self.field_converted(fields::DUE_DATE, |text| {
+            jiff::civil::Date::strptime(SPAYD_DATE_FMT, text)
+        })
+    }
+
+    /// Set the due date from a Jiff civil Date
+    #[cfg(feature = "jiff")]
+    pub fn set_due_date_jiff(&mut self, date: &jiff::civil::Date) {
+        self.set_field_converted(fields::DUE_DATE, date, |date| {
+            date.strftime(SPAYD_DATE_FMT).to_string()
+        })
+    }
+
     /// Get the payment amount as a decimal
     #[cfg(feature = "rust_decimal")]
     pub fn amount(&self) -> Result<Decimal, SpaydError> {
         self.field_converted(fields::AMOUNT, Decimal::from_str)
     }
 
-    /// Set the due date from a decimal
+    /// Set the amount from a decimal. The amount is written as it is, see
+    /// `set_amount_exact` and `set_amount_rounded` for setting amounts with
+    /// the precision required by the currency.
     #[cfg(feature = "rust_decimal")]
     pub fn set_amount(&mut self, amount: &Decimal) {
         self.set_field_converted(fields::AMOUNT, amount, Decimal::to_string)
@@ -227,3 +268,70 @@ mod chrono_tests {
         assert_eq!(spayd.field("DT"), Some("20121231"))
     }
 }
+
+#[cfg(feature = "time")]
+#[cfg(test)]
+mod time_tests {
+    use super::*;
+    use time::{Date, Month};
+
+    #[test]
+    fn due_date_correct_format() {
+        let spayd = Spayd::new_v1_0(vec![("DT", "20121231")]);
+        assert_eq!(
+            spayd.due_date_time(),
+            Ok(Date::from_calendar_date(2012, Month::December, 31).unwrap())
+        )
+    }
+
+    #[test]
+    fn due_date_incorrect_format() {
+        let spayd = Spayd::new_v1_0(vec![("DT", "2012/12/31")]);
+        assert_eq!(
+            spayd.due_date_time(),
+            Err(SpaydError::ConvertError("2012/12/31".into()))
+        )
+    }
+
+    #[test]
+    fn set_due_date_correct_format() {
+        let mut spayd = Spayd::empty_v1_0();
+        let date = Date::from_calendar_date(2012, Month::December, 31).unwrap();
+        spayd.set_due_date_time(&date);
+        assert_eq!(spayd.field("DT"), Some("20121231"));
+
+        let date = Date::from_calendar_date(999, Month::January, 2).unwrap();
+        spayd.set_due_date_time(&date);
+        assert_eq!(spayd.field("DT"), Some("09990102"));
+        assert_eq!(spayd.due_date_time(), Ok(date))
+    }
+}
+
+#[cfg(feature = "jiff")]
+#[cfg(test)]
+mod jiff_tests {
+    use super::*;
+    use jiff::civil::date;
+
+    #[test]
+    fn due_date_correct_format() {
+        let spayd = Spayd::new_v1_0(vec![("DT", "20121231")]);
+        assert_eq!(spayd.due_date_jiff(), Ok(date(2012, 12, 31)))
+    }
+
+    #[test]
+    fn due_date_incorrect_format() {
+        let spayd = Spayd::new_v1_0(vec![("DT", "2012/12/31")]);
+        assert_eq!(
+            spayd.due_date_jiff(),
+            Err(SpaydError::ConvertError("2012/12/31".into()))
+        )
+    }
+
+    #[test]
+    fn set_due_date_correct_format() {
+        let mut spayd = Spayd::empty_v1_0();
+        spayd.set_due_date_jiff(&date(2012, 12, 31));
+        assert_eq!(spayd.field("DT"), Some("20121231"))
+    }
+}
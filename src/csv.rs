@@ -0,0 +1,395 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+use ::csv::{ReaderBuilder, StringRecord, WriterBuilder};
+
+use crate::spayd::check_key;
+use crate::{
+    fields, is_valid_key, BatchLine, BatchReport, Spayd, SpaydError, SpaydSchema, SpaydVersion,
+};
+
+const FORMAT: &str = "CSV";
+
+/// Aliases added by `CsvMapping::with_common_aliases`.
+const COMMON_ALIASES: &[(&str, &str)] = &[
+    ("Account", fields::ACCOUNT),
+    ("IBAN", fields::ACCOUNT),
+    ("Amount", fields::AMOUNT),
+    ("Currency", fields::CURRENCY),
+    ("Reference", fields::REFERENCE),
+    ("Recipient", fields::RECIPIENT),
+    ("Name", fields::RECIPIENT),
+    ("Due date", fields::DUE_DATE),
+    ("Message", fields::MESSAGE),
+    ("Variable symbol", fields::VARIABLE_SYMBOL),
+    ("Specific symbol", fields::SPECIFIC_SYMBOL),
+    ("Constant symbol", fields::CONSTANT_SYMBOL),
+];
+
+fn format_error(line: usize, reason: &str) -> SpaydError {
+    SpaydError::FileFormat {
+        format: FORMAT,
+        line,
+        reason: reason.into(),
+    }
+}
+
+/// Converts between CSV files and SPAYDs, one payment per row.
+///
+/// When reading, a column's header is either a field name such as "AM" or
+/// "X-VS", or an alias for one. Aliases are compared without regard to case
+/// or surrounding whitespace. Empty cells are skipped and each row becomes a
+/// version 1.0 SPAYD, which is validated.
+///
+/// When writing, the header has the field names, starting with the fields
+/// of the standard in the order they're defined, followed by any other
+/// fields in alphabetical order. Only fields used by at least one payment
+/// get a column.
+/// ```
+/// use spayd::CsvMapping;
+///
+/// let csv = "IBAN,Amount,Currency,X-VS\n\
+///            CZ5855000000001265098001,480.50,CZK,1001\n\
+///            ,100.00,CZK,1002\n";
+/// let mapping = CsvMapping::new().with_common_aliases();
+/// let report = mapping.read(csv.as_bytes()).unwrap();
+///
+/// let (line, payment) = report.payments().next().unwrap();
+/// assert_eq!(line, 2);
+/// assert_eq!(payment.field("AM"), Some("480.50"));
+/// assert_eq!(report.errors().next().unwrap().0, 3);
+///
+/// let payments: Vec<_> = report.payments().map(|(_, p)| p.clone()).collect();
+/// assert_eq!(
+///     mapping.to_csv_string(&payments).unwrap(),
+///     "ACC,AM,CC,X-VS\nCZ5855000000001265098001,480.50,CZK,1001\n"
+/// );
+/// ```
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct CsvMapping {
+    aliases: BTreeMap<String, String>,
+    delimiter: Option<u8>,
+}
+
+fn normalize_alias(alias: &str) -> String {
+    alias.trim().to_lowercase()
+}
+
+impl CsvMapping {
+    /// A mapping which only accepts field names as headers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept a column header as an alias for a field. Fails with
+    /// `InvalidKey` if the field name isn't valid, like
+    /// `Spayd::try_set_field`.
+    pub fn with_alias(mut self, alias: &str, field: &str) -> Result<Self, SpaydError> {
+        check_key(field)?;
+        self.aliases.insert(normalize_alias(alias), field.into());
+        Ok(self)
+    }
+
+    /// Accept English headers for the common fields, e.g. "Amount", "IBAN",
+    /// "Due date" and "Variable symbol".
+    pub fn with_common_aliases(mut self) -> Self {
+        for (alias, field) in COMMON_ALIASES {
+            self.aliases.insert(normalize_alias(alias), (*field).into());
+        }
+        self
+    }
+
+    /// Use a delimiter other than a comma, e.g. `b';'` for spreadsheets
+    /// saved with a Czech locale.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// The field for a column header.
+    fn field_for(&self, header: &str) -> Option<String> {
+        if let Some(field) = self.aliases.get(&normalize_alias(header)) {
+            return Some(field.clone());
+        }
+        let header = header.trim();
+        is_valid_key(header).then(|| header.into())
+    }
+
+    fn fields_for(&self, headers: &StringRecord) -> Result<Vec<String>, SpaydError> {
+        let mut columns: Vec<String> = Vec::new();
+        for header in headers {
+            let field = self
+                .field_for(header)
+                .ok_or_else(|| format_error(1, &format!("unknown column '{}'", header)))?;
+            if columns.contains(&field) {
+                return Err(format_error(
+                    1,
+                    &format!("more than one column for {}", field),
+                ));
+            }
+            columns.push(field);
+        }
+        Ok(columns)
+    }
+
+    /// Read a CSV file with a header row. Fails with `FileFormat` if the
+    /// header can't be read or a column isn't a field or alias, and `Io` if
+    /// reading fails. Errors in the rows are reported for each row, with the
+    /// line numbers of the file.
+    pub fn read<R: Read>(&self, mut reader: R) -> Result<BatchReport, SpaydError> {
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .map_err(|e| SpaydError::Io(e.to_string()))?;
+
+        let mut reader = ReaderBuilder::new()
+            .delimiter(self.delimiter.unwrap_or(b','))
+            .flexible(true)
+            .from_reader(data.as_slice());
+        let headers = reader
+            .headers()
+            .map_err(|e| format_error(1, &e.to_string()))?
+            .clone();
+        let columns = self.fields_for(&headers)?;
+
+        let mut lines = Vec::new();
+        let mut record = StringRecord::new();
+        loop {
+            let offset = reader.position().byte() as usize;
+            match reader.read_record(&mut record) {
+                Ok(false) => break,
+                Ok(true) => {
+                    if record.iter().all(|cell| cell.trim().is_empty()) {
+                        continue;
+                    }
+                    let line = line_at(&data, offset);
+                    lines.push(BatchLine {
+                        line,
+                        result: row_to_spayd(&columns, &record, line),
+                    });
+                }
+                Err(error) => {
+                    let line = line_at(&data, offset);
+                    lines.push(BatchLine {
+                        line,
+                        result: Err(format_error(line, &error.to_string())),
+                    });
+                }
+            }
+        }
+        Ok(BatchReport::new(lines))
+    }
+
+    /// Write payments as CSV with a header row. Nothing is written if there
+    /// are no fields.
+    pub fn write<W: Write>(&self, writer: W, payments: &[Spayd]) -> Result<(), SpaydError> {
+        let io_error = |e: ::csv::Error| SpaydError::Io(e.to_string());
+        let columns = columns_for(payments);
+        let mut writer = WriterBuilder::new()
+            .delimiter(self.delimiter.unwrap_or(b','))
+            .from_writer(writer);
+
+        if columns.is_empty() {
+            return Ok(());
+        }
+        writer.write_record(&columns).map_err(io_error)?;
+        for payment in payments {
+            writer
+                .write_record(columns.iter().map(|c| payment.field(c).unwrap_or_default()))
+                .map_err(io_error)?;
+        }
+        writer.flush().map_err(|e| SpaydError::Io(e.to_string()))
+    }
+
+    /// Write payments as a CSV string with a header row.
+    pub fn to_csv_string(&self, payments: &[Spayd]) -> Result<String, SpaydError> {
+        let mut output = Vec::new();
+        self.write(&mut output, payments)?;
+        String::from_utf8(output).map_err(|e| SpaydError::Io(e.to_string()))
+    }
+}
+
+/// The line number of the record starting at a byte offset. Blank lines
+/// before the record are skipped, as the CSV reader does.
+fn line_at(data: &[u8], offset: usize) -> usize {
+    let offset = offset.min(data.len());
+    let start = data[offset..]
+        .iter()
+        .position(|b| !matches!(b, b'\r' | b'\n'))
+        .map_or(data.len(), |skipped| offset + skipped);
+    data[..start].iter().filter(|b| **b == b'\n').count() + 1
+}
+
+fn row_to_spayd(
+    columns: &[String],
+    record: &StringRecord,
+    line: usize,
+) -> Result<Spayd, SpaydError> {
+    if record.len() > columns.len() {
+        return Err(format_error(line, "more cells than columns"));
+    }
+    let spayd = Spayd::new_v1_0(
+        columns
+            .iter()
+            .zip(record.iter())
+            .map(|(field, cell)| (field, cell.trim()))
+            .filter(|(_, cell)| !cell.is_empty()),
+    );
+    spayd.validate()?;
+    Ok(spayd)
+}
+
+/// The fields used by any of the payments, standard fields first.
+fn columns_for(payments: &[Spayd]) -> Vec<&str> {
+    let standard = SpaydSchema::for_version(SpaydVersion::new(1, 0))
+        .map(|(schema, _)| schema.known_fields())
+        .unwrap_or_default();
+    let used = |field: &str| payments.iter().any(|p| p.field(field).is_some());
+
+    let mut columns: Vec<&str> = standard.iter().copied().filter(|f| used(f)).collect();
+    let mut others: Vec<&str> = payments
+        .iter()
+        .flat_map(|p| p.iter().map(|(key, _)| key))
+        .filter(|key| !standard.contains(key))
+        .collect();
+    others.sort();
+    others.dedup();
+    columns.extend(others);
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\
+Account;amount ; CC;Due date;X-ORDER;Note
+CZ5855000000001265098001;480.50;CZK;20261020;A-1;first
+CZ5855000000001265098001;;;;;
+;100;CZK;;;
+
+CZ5855000000001265098001;1.505;CZK;;;
+\"CZ5855000000001265098001\";12;EUR;;;\"two
+lines\"
+";
+
+    fn mapping() -> CsvMapping {
+        CsvMapping::new()
+            .with_common_aliases()
+            .with_alias("note", "MSG")
+            .unwrap()
+            .with_delimiter(b';')
+    }
+
+    #[test]
+    fn read() {
+        let report = mapping().read(CSV.as_bytes()).unwrap();
+        let payments: Vec<(usize, &Spayd)> = report.payments().collect();
+        assert_eq!(payments.len(), 2);
+        assert_eq!(
+            payments[0],
+            (
+                2,
+                &Spayd::new_v1_0(vec![
+                    ("ACC", "CZ5855000000001265098001"),
+                    ("AM", "480.50"),
+                    ("CC", "CZK"),
+                    ("DT", "20261020"),
+                    ("MSG", "first"),
+                    ("X-ORDER", "A-1"),
+                ])
+            )
+        );
+        assert_eq!(
+            payments[1],
+            (
+                3,
+                &Spayd::new_v1_0(vec![("ACC", "CZ5855000000001265098001")])
+            )
+        );
+
+        let errors: Vec<(usize, &str)> = report
+            .errors()
+            .map(|(line, error)| (line, error.kind()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (4, "RequiredFieldMissing"),
+                (6, "AmountPrecision"),
+                (7, "InvalidValue")
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_headers() {
+        assert_eq!(
+            CsvMapping::new().read("ACC,Amount\n".as_bytes()),
+            Err(format_error(1, "unknown column 'Amount'"))
+        );
+        assert_eq!(
+            CsvMapping::new()
+                .with_common_aliases()
+                .read("ACC,IBAN\n".as_bytes()),
+            Err(format_error(1, "more than one column for ACC"))
+        );
+    }
+
+    #[test]
+    fn invalid_alias() {
+        assert_eq!(
+            CsvMapping::new().with_alias("Note", "msg"),
+            Err(SpaydError::InvalidKey("msg".into()))
+        );
+    }
+
+    #[test]
+    fn extra_cells() {
+        let report = CsvMapping::new()
+            .read("ACC\nCZ5855000000001265098001,1\n".as_bytes())
+            .unwrap();
+        assert_eq!(
+            report.errors().next(),
+            Some((2, &format_error(2, "more cells than columns")))
+        );
+
+        let report = CsvMapping::new()
+            .read(&b"ACC\n\xff\nCZ5855000000001265098001\n"[..])
+            .unwrap();
+        assert_eq!(
+            report.errors().next().map(|(line, e)| (line, e.kind())),
+            Some((2, "FileFormat"))
+        );
+        assert_eq!(report.payments().next().map(|(line, _)| line), Some(3));
+    }
+
+    #[test]
+    fn write() {
+        let payments = [
+            Spayd::new_v1_0(vec![
+                ("X-ORDER", "A-1"),
+                ("MSG", "Faktura; 1"),
+                ("AM", "10"),
+                ("ACC", "CZ5855000000001265098001"),
+            ]),
+            Spayd::new_v1_0(vec![
+                ("ACC", "CZ5855000000001265098001"),
+                ("X-VS", "1001"),
+                ("A-FIELD", "x"),
+            ]),
+        ];
+        let csv = mapping().to_csv_string(&payments).unwrap();
+        assert_eq!(
+            csv,
+            "ACC;AM;MSG;X-VS;A-FIELD;X-ORDER\n\
+             CZ5855000000001265098001;10;\"Faktura; 1\";;;A-1\n\
+             CZ5855000000001265098001;;;1001;x;\n"
+        );
+
+        let report = mapping().read(csv.as_bytes()).unwrap();
+        let read: Vec<Spayd> = report.payments().map(|(_, p)| p.clone()).collect();
+        assert_eq!(read, payments);
+        assert_eq!(CsvMapping::new().to_csv_string(&[]).unwrap(), "");
+    }
+}
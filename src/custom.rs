@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use crate::spayd::check_key;
+use crate::{Spayd, SpaydError};
+
+/// A function checking the text of a field.
+pub type FieldValidator = fn(&str) -> Result<(), SpaydError>;
+
+/// A typed definition of a custom field, usually a private `X-` field.
+///
+/// Once registered in a `FieldRegistry`, `Spayd::validate_with` checks the
+/// field whenever it's present. The value can be read and written with
+/// `Spayd::custom_field` and `Spayd::set_custom_field`.
+/// ```
+/// use spayd::{CustomField, FieldRegistry, Spayd, SpaydError};
+///
+/// struct OrderNumber;
+///
+/// impl CustomField for OrderNumber {
+///     const KEY: &'static str = "X-ORDER";
+///     type Value = u32;
+///
+///     fn decode(text: &str) -> Result<u32, SpaydError> {
+///         text.parse().or(Err(SpaydError::ConvertError(text.into())))
+///     }
+///
+///     fn encode(value: &u32) -> String {
+///         value.to_string()
+///     }
+/// }
+///
+/// let mut registry = FieldRegistry::new();
+/// registry.register_field::<OrderNumber>().unwrap();
+///
+/// let mut payment = Spayd::new_v1_0(vec![("ACC", "CZ1355000000000000222885")]);
+/// payment.set_custom_field::<OrderNumber>(&1234);
+/// assert_eq!(payment.custom_field::<OrderNumber>(), Ok(1234));
+///
+/// payment.set_field("X-ORDER", "not a number");
+/// assert!(payment.validate().is_ok());
+/// assert!(payment.validate_with(&registry).is_err());
+/// ```
+pub trait CustomField {
+    /// The field name.
+    const KEY: &'static str;
+    /// The type of the field's value.
+    type Value;
+
+    /// Convert the text of the field into a value.
+    fn decode(text: &str) -> Result<Self::Value, SpaydError>;
+
+    /// Convert a value into the text of the field.
+    fn encode(value: &Self::Value) -> String;
+
+    /// Check the text of the field. By default the field is valid if it
+    /// can be decoded.
+    fn validate(text: &str) -> Result<(), SpaydError> {
+        Self::decode(text).map(|_| ())
+    }
+}
+
+/// Validators for custom fields, used by `Spayd::validate_with`. Each
+/// application keeps its own registry, so registering a field doesn't
+/// change how other code using this crate validates SPAYDs.
+#[derive(Clone, Default, Debug)]
+pub struct FieldRegistry {
+    validators: BTreeMap<String, FieldValidator>,
+}
+
+impl FieldRegistry {
+    /// A registry with no custom fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a custom field. Registering a field with the same key again
+    /// replaces its validator. Fails with `InvalidKey` if the key isn't a
+    /// valid field name.
+    pub fn register_field<F: CustomField>(&mut self) -> Result<(), SpaydError> {
+        self.register_validator(F::KEY, F::validate)
+    }
+
+    /// Register a validator for a field without a typed definition. Fails
+    /// with `InvalidKey` if the key isn't a valid field name, as such a field
+    /// can never be present.
+    pub fn register_validator(
+        &mut self,
+        key: &str,
+        validator: FieldValidator,
+    ) -> Result<(), SpaydError> {
+        check_key(key)?;
+        self.validators.insert(key.to_owned(), validator);
+        Ok(())
+    }
+
+    /// Remove a registered field. Returns false if the field wasn't
+    /// registered.
+    pub fn unregister_field(&mut self, key: &str) -> bool {
+        self.validators.remove(key).is_some()
+    }
+
+    /// Check if a custom field has been registered.
+    pub fn is_registered(&self, key: &str) -> bool {
+        self.validators.contains_key(key)
+    }
+}
+
+impl Spayd {
+    /// Get the value of a custom field.
+    pub fn custom_field<F: CustomField>(&self) -> Result<F::Value, SpaydError> {
+        match self.field(F::KEY) {
+            Some(text) => F::decode(text),
+            None => Err(SpaydError::FieldMissing(F::KEY.into())),
+        }
+    }
+
+    /// Set the value of a custom field.
+    pub fn set_custom_field<F: CustomField>(&mut self, value: &F::Value) {
+        self.set_field(F::KEY, F::encode(value));
+    }
+
+    /// Validate the SPAYD as `validate` does, then run the registry's
+    /// validators for every custom field present.
+    pub fn validate_with(&self, registry: &FieldRegistry) -> Result<(), SpaydError> {
+        self.validate()?;
+        for (key, value) in self.iter() {
+            if let Some(validator) = registry.validators.get(key) {
+                validator(value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Customer;
+
+    impl CustomField for Customer {
+        const KEY: &'static str = "X-CUSTOMER";
+        type Value = String;
+
+        fn decode(text: &str) -> Result<String, SpaydError> {
+            if text.len() == 6 && text.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                Ok(text.to_owned())
+            } else {
+                Err(SpaydError::ConvertError(text.into()))
+            }
+        }
+
+        fn encode(value: &String) -> String {
+            value.to_owned()
+        }
+    }
+
+    fn payment() -> Spayd {
+        Spayd::new_v1_0(vec![("ACC", "CZ5855000000001265098001")])
+    }
+
+    #[test]
+    fn typed_get_set() {
+        let mut spayd = payment();
+        assert_eq!(
+            spayd.custom_field::<Customer>(),
+            Err(SpaydError::FieldMissing("X-CUSTOMER".into()))
+        );
+
+        spayd.set_custom_field::<Customer>(&"ABC123".into());
+        assert_eq!(spayd.field("X-CUSTOMER"), Some("ABC123"));
+        assert_eq!(spayd.custom_field::<Customer>(), Ok("ABC123".into()));
+    }
+
+    #[test]
+    fn registered_validation() {
+        let mut spayd = payment();
+        spayd.set_field("X-CUSTOMER", "??");
+
+        let mut registry = FieldRegistry::new();
+        registry.register_field::<Customer>().unwrap();
+        assert!(registry.is_registered("X-CUSTOMER"));
+        assert_eq!(
+            spayd.validate_with(&registry),
+            Err(SpaydError::ConvertError("??".into()))
+        );
+        assert_eq!(spayd.validate(), Ok(()));
+        assert_eq!(spayd.validate_with(&FieldRegistry::new()), Ok(()));
+
+        spayd.set_field("X-CUSTOMER", "XYZ789");
+        assert_eq!(spayd.validate_with(&registry), Ok(()));
+    }
+
+    #[test]
+    fn untyped_validator() {
+        let mut spayd = payment();
+        spayd.set_field("X-BRANCH", "12");
+
+        let mut registry = FieldRegistry::new();
+        registry
+            .register_validator("X-BRANCH", |text| match text {
+                "01" | "02" => Ok(()),
+                _ => Err(SpaydError::ConvertError(text.into())),
+            })
+            .unwrap();
+        assert_eq!(
+            spayd.validate_with(&registry),
+            Err(SpaydError::ConvertError("12".into()))
+        );
+
+        assert!(registry.unregister_field("X-BRANCH"));
+        assert!(!registry.unregister_field("X-BRANCH"));
+        assert_eq!(spayd.validate_with(&registry), Ok(()));
+    }
+
+    #[test]
+    fn invalid_keys() {
+        let mut registry = FieldRegistry::new();
+        for key in ["x-branch", "X*BRANCH", ""] {
+            assert_eq!(
+                registry.register_validator(key, |_| Ok(())),
+                Err(SpaydError::InvalidKey(key.into()))
+            );
+            assert!(!registry.is_registered(key));
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::reference::mod97;
+use crate::SpaydError;
+
+/// Weights for the modulo 11 check of the prefix, from the left.
+const PREFIX_WEIGHTS: [u64; 6] = [10, 5, 8, 4, 2, 1];
+
+/// Weights for the modulo 11 check of the account number, from the left.
+const NUMBER_WEIGHTS: [u64; 10] = [6, 3, 7, 9, 10, 5, 8, 4, 2, 1];
+
+/// A Czech domestic account number in the form `prefix-number/bank`, e.g.
+/// "19-2000145399/0800".
+///
+/// Domestic numbers are used by Czech bank statements and payment orders,
+/// while SPAYD uses IBANs. Both the prefix and the number are checked with
+/// the weighted modulo 11 rule.
+/// ```
+/// use spayd::DomesticAccount;
+///
+/// let account: DomesticAccount = "19-2000145399/0800".parse().unwrap();
+/// assert_eq!(account.to_iban_string(), "CZ6508000000192000145399");
+///
+/// let from_iban = DomesticAccount::from_iban("CZ65 0800 0000 1920 0014 5399").unwrap();
+/// assert_eq!(from_iban, account);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct DomesticAccount {
+    prefix: u32,
+    number: u64,
+    bank_code: u16,
+}
+
+fn weighted_sum(value: u64, weights: &[u64]) -> u64 {
+    let digits = format!("{:0width$}", value, width = weights.len());
+    digits
+        .bytes()
+        .zip(weights)
+        .map(|(digit, weight)| u64::from(digit - b'0') * weight)
+        .sum()
+}
+
+fn parse_digits<T: FromStr>(text: &str, max_length: usize) -> Option<T> {
+    if (1..=max_length).contains(&text.len()) && text.bytes().all(|b| b.is_ascii_digit()) {
+        text.parse().ok()
+    } else {
+        None
+    }
+}
+
+impl DomesticAccount {
+    /// Create an account from its parts. Fails with `ConvertError` if the
+    /// parts are too long, the number is zero or a modulo 11 check fails.
+    pub fn new(prefix: u32, number: u64, bank_code: u16) -> Result<Self, SpaydError> {
+        let account = Self {
+            prefix,
+            number,
+            bank_code,
+        };
+        let valid = prefix < 1_000_000
+            && number > 0
+            && number < 10_000_000_000
+            && bank_code < 10_000
+            && weighted_sum(prefix.into(), &PREFIX_WEIGHTS).is_multiple_of(11)
+            && weighted_sum(number, &NUMBER_WEIGHTS).is_multiple_of(11);
+        if valid {
+            Ok(account)
+        } else {
+            Err(SpaydError::ConvertError(account.to_string()))
+        }
+    }
+
+    /// Convert a Czech IBAN to a domestic account number. Spaces are
+    /// ignored. Fails with `ConvertError` for other countries or incorrect
+    /// check digits.
+    pub fn from_iban(iban: &str) -> Result<Self, SpaydError> {
+        let error = || SpaydError::ConvertError(iban.into());
+        let compact: String = iban.chars().filter(|c| !c.is_whitespace()).collect();
+        let bban = compact
+            .strip_prefix("CZ")
+            .and_then(|rest| rest.get(2..))
+            .filter(|bban| bban.len() == 20)
+            .ok_or_else(error)?;
+        if mod97(&format!("{}{}", bban, &compact[..4])) != Some(1) {
+            return Err(error());
+        }
+
+        let bank_code = parse_digits(&bban[..4], 4).ok_or_else(error)?;
+        let prefix = parse_digits(&bban[4..10], 6).ok_or_else(error)?;
+        let number = parse_digits(&bban[10..], 10).ok_or_else(error)?;
+        Self::new(prefix, number, bank_code).or(Err(error()))
+    }
+
+    /// The account prefix, zero if there isn't one.
+    pub fn prefix(&self) -> u32 {
+        self.prefix
+    }
+
+    /// The account number.
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+
+    /// The four digit bank code.
+    pub fn bank_code(&self) -> u16 {
+        self.bank_code
+    }
+
+    /// The Czech IBAN for the account, without spaces.
+    pub fn to_iban_string(&self) -> String {
+        let bban = format!("{:04}{:06}{:010}", self.bank_code, self.prefix, self.number);
+        let remainder = mod97(&format!("{}CZ00", bban)).unwrap_or_default();
+        format!("CZ{:02}{}", 98 - remainder, bban)
+    }
+}
+
+impl Display for DomesticAccount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.prefix != 0 {
+            write!(f, "{}-", self.prefix)?;
+        }
+        write!(f, "{}/{:04}", self.number, self.bank_code)
+    }
+}
+
+impl FromStr for DomesticAccount {
+    type Err = SpaydError;
+
+    /// Parse an account number in the form `prefix-number/bank` or
+    /// `number/bank`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || SpaydError::ConvertError(text.into());
+        let (account, bank_code) = text.trim().split_once('/').ok_or_else(error)?;
+        let (prefix, number) = account.split_once('-').unwrap_or(("0", account));
+
+        Self::new(
+            parse_digits(prefix, 6).ok_or_else(error)?,
+            parse_digits(number, 10).ok_or_else(error)?,
+            parse_digits(bank_code, 4)
+                .filter(|_| bank_code.len() == 4)
+                .ok_or_else(error)?,
+        )
+        .or(Err(error()))
+    }
+}
+
+/// The prefix, number and bank code (if given) of an account written as a
+/// Czech IBAN, `prefix-number/bank`, `prefix-number` or 16 digits as in
+/// GPC statements.
+fn account_parts(text: &str) -> Option<(u32, u64, Option<u16>)> {
+    if let Ok(account) = DomesticAccount::from_iban(text) {
+        return Some((account.prefix, account.number, Some(account.bank_code)));
+    }
+
+    let text = text.trim();
+    let (account, bank_code) = match text.split_once('/') {
+        Some((account, bank_code)) => (account, Some(parse_digits(bank_code, 4)?)),
+        None => (text, None),
+    };
+    let (prefix, number) = match account.split_once('-') {
+        Some((prefix, number)) => (parse_digits(prefix, 6)?, parse_digits(number, 10)?),
+        None if account.len() == 16 => (
+            parse_digits(account.get(..6)?, 6)?,
+            parse_digits(account.get(6..)?, 10)?,
+        ),
+        None => (0, parse_digits(account, 10)?),
+    };
+    Some((prefix, number, bank_code))
+}
+
+/// Check if two account numbers refer to the same account. Czech accounts
+/// are compared by prefix, number and bank code, where the bank code is only
+/// compared if both have one. Other accounts are compared as IBANs without
+/// spaces or a BIC.
+pub fn same_account(a: &str, b: &str) -> bool {
+    let iban = |text: &str| {
+        text.split('+')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_uppercase()
+    };
+    let (a, b) = (iban(a), iban(b));
+
+    match (account_parts(&a), account_parts(&b)) {
+        (Some((prefix_a, number_a, bank_a)), Some((prefix_b, number_b, bank_b))) => {
+            prefix_a == prefix_b
+                && number_a == number_b
+                && (bank_a.is_none() || bank_b.is_none() || bank_a == bank_b)
+        }
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_format() {
+        let account: DomesticAccount = "19-2000145399/0800".parse().unwrap();
+        assert_eq!(account.prefix(), 19);
+        assert_eq!(account.number(), 2000145399);
+        assert_eq!(account.bank_code(), 800);
+        assert_eq!(account.to_string(), "19-2000145399/0800");
+
+        let account: DomesticAccount = "1265098001/5500".parse().unwrap();
+        assert_eq!(account.to_string(), "1265098001/5500");
+    }
+
+    #[test]
+    fn invalid() {
+        for text in [
+            "2000145399",
+            "19-2000145398/0800",
+            "18-2000145399/0800",
+            "19-2000145399/800",
+            "0/0800",
+        ] {
+            assert_eq!(
+                text.parse::<DomesticAccount>(),
+                Err(SpaydError::ConvertError(text.into())),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn iban_conversion() {
+        let account = DomesticAccount::new(0, 1265098001, 5500).unwrap();
+        assert_eq!(account.to_iban_string(), "CZ5855000000001265098001");
+        assert_eq!(
+            DomesticAccount::from_iban("CZ5855000000001265098001"),
+            Ok(account)
+        );
+
+        assert!(DomesticAccount::from_iban("CZ5955000000001265098001").is_err());
+        assert!(DomesticAccount::from_iban("SK3112000000198742637541").is_err());
+    }
+
+    #[test]
+    fn compare_accounts() {
+        let iban = "CZ6508000000192000145399";
+        assert!(same_account(iban, "19-2000145399/0800"));
+        assert!(same_account(iban, "19-2000145399"));
+        assert!(same_account(iban, "0000192000145399"));
+        assert!(same_account("CZ65 0800 0000 1920 0014 5399+GIBACZPX", iban));
+        assert!(!same_account(iban, "19-2000145399/0100"));
+        assert!(!same_account(iban, "CZ5855000000001265098001"));
+        assert!(same_account(
+            "DE89370400440532013000",
+            "de89 3704 0044 0532 0130 00"
+        ));
+    }
+
+    #[test]
+    fn compare_non_ascii() {
+        let iban = "CZ6508000000192000145399";
+        // 16 bytes with a character across the prefix boundary
+        assert!(!same_account(iban, "00000ř192000145"));
+        assert!(!same_account("0000€192000145/0800", iban));
+    }
+}
@@ -1,6 +1,8 @@
 use nom::error::Error as NomError;
 use thiserror::Error;
 
+use crate::SpaydVersion;
+
 /// Errors encountered when parsing and validating SPAYD values.
 #[derive(Error, Debug, PartialEq)]
 pub enum SpaydError {
@@ -23,6 +25,74 @@ pub enum SpaydError {
     /// The SPAYD value doesn't have the field for conversion.
     #[error("field '{0}' is missing")]
     FieldMissing(String),
+    /// The major version of the SPAYD isn't supported, so its fields can't
+    /// be interpreted.
+    #[error("unsupported version {}.{}", .0.major, .0.minor)]
+    UnsupportedVersion(SpaydVersion),
+    /// A field name isn't allowed by the standard. Names may only contain
+    /// uppercase letters, digits and `-`.
+    #[error("invalid field name '{0}'")]
+    InvalidKey(String),
+    /// A field value contains characters which aren't allowed, such as
+    /// control characters. The field name is supplied in the error.
+    #[error("field '{0}' contains characters which aren't allowed")]
+    InvalidValue(String),
+    /// A field value contains a `%` which isn't followed by two hex digits.
+    #[error("malformed percent escape in '{0}'")]
+    MalformedEscape(String),
+    /// A field value's percent escapes don't decode to valid UTF-8.
+    #[error("percent escapes in '{0}' aren't valid UTF-8")]
+    InvalidUtf8(String),
+    /// The amount has more decimal places than its currency allows.
+    #[error("amount {amount} has more than {decimals} decimal places allowed for {currency}")]
+    AmountPrecision {
+        amount: String,
+        currency: String,
+        decimals: u32,
+    },
+    /// A file couldn't be read.
+    #[error("couldn't read file: {0}")]
+    Io(String),
+    /// A file or document isn't in the expected format. The format, line
+    /// number (starting from 1) and reason are supplied in the error.
+    #[error("invalid {format} on line {line}: {reason}")]
+    FileFormat {
+        format: &'static str,
+        line: usize,
+        reason: String,
+    },
+    /// A payment can't be written in a file format. The format, position of
+    /// the payment (starting from 0) and reason are supplied in the error.
+    #[error("payment {index} can't be exported to {format}: {reason}")]
+    ExportError {
+        format: &'static str,
+        index: usize,
+        reason: String,
+    },
+}
+
+impl SpaydError {
+    /// The name of the error variant, e.g. "ParseError", for grouping and
+    /// reporting errors.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::ParseError(_) => "ParseError",
+            Self::RequiredFieldMissing(_) => "RequiredFieldMissing",
+            #[cfg(feature = "crc32")]
+            Self::Crc32Failed => "Crc32Failed",
+            Self::ConvertError(_) => "ConvertError",
+            Self::FieldMissing(_) => "FieldMissing",
+            Self::UnsupportedVersion(_) => "UnsupportedVersion",
+            Self::InvalidKey(_) => "InvalidKey",
+            Self::InvalidValue(_) => "InvalidValue",
+            Self::MalformedEscape(_) => "MalformedEscape",
+            Self::InvalidUtf8(_) => "InvalidUtf8",
+            Self::AmountPrecision { .. } => "AmountPrecision",
+            Self::Io(_) => "Io",
+            Self::FileFormat { .. } => "FileFormat",
+            Self::ExportError { .. } => "ExportError",
+        }
+    }
 }
 
 impl From<NomError<&str>> for SpaydError {
@@ -0,0 +1,503 @@
+//! A C ABI for using SPAYD values from other languages.
+//!
+//! SPAYD values are passed across the boundary as opaque `Spayd` handles
+//! which must be released with `spayd_free`. Strings returned by the library
+//! are allocated by Rust and must be released with `spayd_string_free`.
+//! Every fallible function returns a `SpaydStatus` code, with any result
+//! written through an output pointer.
+//!
+//! The C header for this module is generated by cbindgen and can be found
+//! in `include/spayd.h`. The library itself is built with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or
+//! `staticlib`).
+
+use std::ffi::{c_char, c_int, CStr, CString};
+
+use crate::{Spayd, SpaydError};
+
+/// Status codes returned by the C API.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpaydStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// The text couldn't be parsed as a SPAYD.
+    ParseError = 1,
+    /// A field required by the SPAYD standard is missing.
+    RequiredFieldMissing = 2,
+    /// The CRC32 checksum failed.
+    Crc32Failed = 3,
+    /// A field value has an incorrect format.
+    ConvertError = 4,
+    /// The requested field isn't present.
+    FieldMissing = 5,
+    /// The major version of the SPAYD isn't supported.
+    UnsupportedVersion = 6,
+    /// A field name isn't allowed by the standard.
+    InvalidKey = 7,
+    /// A field value contains a malformed percent escape.
+    MalformedEscape = 8,
+    /// A field value's percent escapes don't decode to valid UTF-8.
+    InvalidEscapedUtf8 = 9,
+    /// The amount has more decimal places than its currency allows.
+    AmountPrecision = 10,
+    /// A file couldn't be read.
+    Io = 11,
+    /// A file or document isn't in the expected format.
+    FileFormat = 12,
+    /// A payment can't be written in a file format.
+    ExportError = 13,
+    /// A field value contains characters which aren't allowed.
+    InvalidValue = 14,
+    /// A required pointer argument was null.
+    NullPointer = 100,
+    /// A string argument wasn't valid UTF-8.
+    InvalidUtf8 = 101,
+    /// A string argument or value contained an interior nul byte.
+    InteriorNul = 102,
+}
+
+impl From<&SpaydError> for SpaydStatus {
+    fn from(error: &SpaydError) -> Self {
+        match error {
+            SpaydError::ParseError(_) => Self::ParseError,
+            SpaydError::RequiredFieldMissing(_) => Self::RequiredFieldMissing,
+            #[cfg(feature = "crc32")]
+            SpaydError::Crc32Failed => Self::Crc32Failed,
+            SpaydError::ConvertError(_) => Self::ConvertError,
+            SpaydError::FieldMissing(_) => Self::FieldMissing,
+            SpaydError::UnsupportedVersion(_) => Self::UnsupportedVersion,
+            SpaydError::InvalidKey(_) => Self::InvalidKey,
+            SpaydError::InvalidValue(_) => Self::InvalidValue,
+            SpaydError::MalformedEscape(_) => Self::MalformedEscape,
+            SpaydError::InvalidUtf8(_) => Self::InvalidEscapedUtf8,
+            SpaydError::AmountPrecision { .. } => Self::AmountPrecision,
+            SpaydError::Io(_) => Self::Io,
+            SpaydError::FileFormat { .. } => Self::FileFormat,
+            SpaydError::ExportError { .. } => Self::ExportError,
+        }
+    }
+}
+
+impl From<SpaydError> for SpaydStatus {
+    fn from(error: SpaydError) -> Self {
+        Self::from(&error)
+    }
+}
+
+/// Borrow a C string argument as a `&str`.
+///
+/// # Safety
+/// `text` must be null or point to a valid nul terminated string.
+unsafe fn str_arg<'a>(text: *const c_char) -> Result<&'a str, SpaydStatus> {
+    if text.is_null() {
+        return Err(SpaydStatus::NullPointer);
+    }
+    CStr::from_ptr(text)
+        .to_str()
+        .or(Err(SpaydStatus::InvalidUtf8))
+}
+
+/// Hand a Rust string over to the caller through an output pointer.
+///
+/// # Safety
+/// `out` must be null or valid for writes.
+unsafe fn write_string(out: *mut *mut c_char, text: String) -> SpaydStatus {
+    if out.is_null() {
+        return SpaydStatus::NullPointer;
+    }
+    match CString::new(text) {
+        Ok(text) => {
+            *out = text.into_raw();
+            SpaydStatus::Ok
+        }
+        Err(_) => SpaydStatus::InteriorNul,
+    }
+}
+
+/// Convert a result into a status code.
+fn status<T>(result: Result<T, SpaydError>) -> SpaydStatus {
+    match result {
+        Ok(_) => SpaydStatus::Ok,
+        Err(e) => e.into(),
+    }
+}
+
+/// Parse and validate SPAYD text. On success a new handle is written to
+/// `out` and must be released with `spayd_free`.
+///
+/// # Safety
+/// `text` must be a valid nul terminated string and `out` must be valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn spayd_parse(text: *const c_char, out: *mut *mut Spayd) -> SpaydStatus {
+    if out.is_null() {
+        return SpaydStatus::NullPointer;
+    }
+    let text = match str_arg(text) {
+        Ok(text) => text,
+        Err(status) => return status,
+    };
+    match text.parse::<Spayd>() {
+        Ok(spayd) => {
+            *out = Box::into_raw(Box::new(spayd));
+            SpaydStatus::Ok
+        }
+        Err(e) => e.into(),
+    }
+}
+
+/// Create an empty version 1.0 SPAYD. The handle must be released with
+/// `spayd_free`.
+#[no_mangle]
+pub extern "C" fn spayd_new_v1_0() -> *mut Spayd {
+    Box::into_raw(Box::new(Spayd::empty_v1_0()))
+}
+
+/// Release a SPAYD handle. Passing null is allowed and does nothing.
+///
+/// # Safety
+/// `spayd` must be null or a handle returned by this library which hasn't
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn spayd_free(spayd: *mut Spayd) {
+    if !spayd.is_null() {
+        drop(Box::from_raw(spayd));
+    }
+}
+
+/// Release a string returned by this library. Passing null is allowed and
+/// does nothing.
+///
+/// # Safety
+/// `text` must be null or a string returned by this library which hasn't
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn spayd_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+/// Validate the SPAYD as `Spayd::validate` does. The status names the first
+/// problem found, matching the error kind (`SpaydError::kind`) of the Rust
+/// API:
+/// - `SPAYD_STATUS_INVALID_KEY` or `SPAYD_STATUS_INVALID_VALUE` if a field
+///   name or value isn't allowed, e.g. an empty value,
+/// - `SPAYD_STATUS_UNSUPPORTED_VERSION` if the major version isn't known,
+/// - `SPAYD_STATUS_REQUIRED_FIELD_MISSING` if a required field is missing,
+/// - `SPAYD_STATUS_AMOUNT_PRECISION` if the amount has more decimal places
+///   than its currency allows,
+/// - `SPAYD_STATUS_CRC32_FAILED` if the CRC32 field doesn't match.
+///
+/// # Safety
+/// `spayd` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn spayd_validate(spayd: *const Spayd) -> SpaydStatus {
+    match spayd.as_ref() {
+        Some(spayd) => status(spayd.validate()),
+        None => SpaydStatus::NullPointer,
+    }
+}
+
+/// Get the value of a field. The value is written to `out` and must be
+/// released with `spayd_string_free`. Returns `SPAYD_STATUS_FIELD_MISSING`
+/// when the field isn't present.
+///
+/// # Safety
+/// `spayd` must be a valid handle, `key` a valid nul terminated string and
+/// `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn spayd_get_field(
+    spayd: *const Spayd,
+    key: *const c_char,
+    out: *mut *mut c_char,
+) -> SpaydStatus {
+    let Some(spayd) = spayd.as_ref() else {
+        return SpaydStatus::NullPointer;
+    };
+    let key = match str_arg(key) {
+        Ok(key) => key,
+        Err(status) => return status,
+    };
+    match spayd.field(key) {
+        Some(value) => write_string(out, value.to_owned()),
+        None => SpaydStatus::FieldMissing,
+    }
+}
+
+/// Set the value of a field. Returns `SPAYD_STATUS_INVALID_KEY` if the key
+/// isn't a valid field name.
+///
+/// # Safety
+/// `spayd` must be a valid handle, `key` and `value` must be valid nul
+/// terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn spayd_set_field(
+    spayd: *mut Spayd,
+    key: *const c_char,
+    value: *const c_char,
+) -> SpaydStatus {
+    let Some(spayd) = spayd.as_mut() else {
+        return SpaydStatus::NullPointer;
+    };
+    let (key, value) = match (str_arg(key), str_arg(value)) {
+        (Ok(key), Ok(value)) => (key, value),
+        (Err(status), _) | (_, Err(status)) => return status,
+    };
+    status(spayd.try_set_field(key, value))
+}
+
+/// Format the SPAYD as text. The text is written to `out` and must be
+/// released with `spayd_string_free`.
+///
+/// # Safety
+/// `spayd` must be a valid handle and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn spayd_to_string(
+    spayd: *const Spayd,
+    out: *mut *mut c_char,
+) -> SpaydStatus {
+    match spayd.as_ref() {
+        Some(spayd) => write_string(out, spayd.to_string()),
+        None => SpaydStatus::NullPointer,
+    }
+}
+
+/// Perform a CRC32 integrity check. As the CRC32 field is optional, the
+/// check passes when no checksum is present. If `provided` isn't null, it
+/// is set to whether a checksum was present.
+///
+/// # Safety
+/// `spayd` must be a valid handle and `provided` must be null or valid
+/// for writes.
+#[cfg(feature = "crc32")]
+#[no_mangle]
+pub unsafe extern "C" fn spayd_check_crc32(
+    spayd: *const Spayd,
+    provided: *mut bool,
+) -> SpaydStatus {
+    let Some(spayd) = spayd.as_ref() else {
+        return SpaydStatus::NullPointer;
+    };
+    match spayd.check_crc32() {
+        Ok(ok) => {
+            if !provided.is_null() {
+                *provided = ok == crate::Crc32Ok::Passed;
+            }
+            SpaydStatus::Ok
+        }
+        Err(e) => e.into(),
+    }
+}
+
+impl SpaydStatus {
+    const ALL: [Self; 18] = [
+        Self::Ok,
+        Self::ParseError,
+        Self::RequiredFieldMissing,
+        Self::Crc32Failed,
+        Self::ConvertError,
+        Self::FieldMissing,
+        Self::UnsupportedVersion,
+        Self::InvalidKey,
+        Self::MalformedEscape,
+        Self::InvalidEscapedUtf8,
+        Self::AmountPrecision,
+        Self::Io,
+        Self::FileFormat,
+        Self::ExportError,
+        Self::InvalidValue,
+        Self::NullPointer,
+        Self::InvalidUtf8,
+        Self::InteriorNul,
+    ];
+
+    /// The status with a numeric code, if there is one.
+    fn from_code(code: c_int) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|status| *status as c_int == code)
+    }
+
+    fn message(self) -> &'static CStr {
+        match self {
+            Self::Ok => c"ok",
+            Self::ParseError => c"couldn't parse text",
+            Self::RequiredFieldMissing => c"a required field is missing",
+            Self::Crc32Failed => c"the data doesn't match the CRC32 checksum",
+            Self::ConvertError => c"a field has an incorrect format",
+            Self::FieldMissing => c"the field is missing",
+            Self::UnsupportedVersion => c"the version isn't supported",
+            Self::InvalidKey => c"invalid field name",
+            Self::MalformedEscape => c"malformed percent escape",
+            Self::InvalidEscapedUtf8 => c"percent escapes aren't valid UTF-8",
+            Self::AmountPrecision => c"the amount has too many decimal places for the currency",
+            Self::Io => c"couldn't read file",
+            Self::FileFormat => c"the file isn't in the expected format",
+            Self::ExportError => c"a payment can't be exported to the file format",
+            Self::InvalidValue => c"a field value contains characters which aren't allowed",
+            Self::NullPointer => c"a required pointer was null",
+            Self::InvalidUtf8 => c"a string wasn't valid UTF-8",
+            Self::InteriorNul => c"a string contained a nul byte",
+        }
+    }
+}
+
+/// Get a static description of a status code. The returned string must
+/// not be freed. Codes which aren't a `SpaydStatus` give "unknown status".
+#[no_mangle]
+pub extern "C" fn spayd_status_message(status: c_int) -> *const c_char {
+    SpaydStatus::from_code(status)
+        .map_or(c"unknown status", SpaydStatus::message)
+        .as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    fn null_out<T>() -> *mut T {
+        ptr::null_mut()
+    }
+
+    unsafe fn take_string(text: *mut c_char) -> String {
+        let owned = CStr::from_ptr(text).to_str().unwrap().to_owned();
+        spayd_string_free(text);
+        owned
+    }
+
+    #[test]
+    fn parse_and_get_field() {
+        unsafe {
+            let mut spayd = null_out();
+            let status = spayd_parse(
+                c"SPD*1.0*ACC:CZ5855000000001265098001*AM:480.50".as_ptr(),
+                &mut spayd,
+            );
+            assert_eq!(status, SpaydStatus::Ok);
+
+            let mut value = null_out();
+            assert_eq!(
+                spayd_get_field(spayd, c"AM".as_ptr(), &mut value),
+                SpaydStatus::Ok
+            );
+            assert_eq!(take_string(value), "480.50");
+
+            assert_eq!(
+                spayd_get_field(spayd, c"MSG".as_ptr(), &mut value),
+                SpaydStatus::FieldMissing
+            );
+            spayd_free(spayd);
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        unsafe {
+            let mut spayd = null_out();
+            assert_eq!(
+                spayd_parse(c"SPD*1.0*ACC".as_ptr(), &mut spayd),
+                SpaydStatus::ParseError
+            );
+            assert_eq!(
+                spayd_parse(c"SPD*1.0*AM:100".as_ptr(), &mut spayd),
+                SpaydStatus::RequiredFieldMissing
+            );
+            assert_eq!(
+                spayd_parse(ptr::null(), &mut spayd),
+                SpaydStatus::NullPointer
+            );
+            assert!(spayd.is_null());
+        }
+    }
+
+    #[test]
+    fn build_and_format() {
+        unsafe {
+            let spayd = spayd_new_v1_0();
+            assert_eq!(spayd_validate(spayd), SpaydStatus::RequiredFieldMissing);
+            assert_eq!(
+                spayd_set_field(spayd, c"ACC".as_ptr(), c"CZ5855000000001265098001".as_ptr()),
+                SpaydStatus::Ok
+            );
+            assert_eq!(
+                spayd_set_field(spayd, c"MSG".as_ptr(), c"A*B".as_ptr()),
+                SpaydStatus::Ok
+            );
+            assert_eq!(spayd_validate(spayd), SpaydStatus::Ok);
+            assert_eq!(
+                spayd_set_field(spayd, c"msg".as_ptr(), c"A*B".as_ptr()),
+                SpaydStatus::InvalidKey
+            );
+
+            let mut text = null_out();
+            assert_eq!(spayd_to_string(spayd, &mut text), SpaydStatus::Ok);
+            assert_eq!(
+                take_string(text),
+                "SPD*1.0*ACC:CZ5855000000001265098001*MSG:A%2AB"
+            );
+            spayd_free(spayd);
+        }
+    }
+
+    #[cfg(feature = "crc32")]
+    #[test]
+    fn crc32() {
+        unsafe {
+            let mut spayd = null_out();
+            spayd_parse(
+                c"SPD*1.0*ACC:CZ5855000000001265098001*AM:100.00*CC:CZK*CRC32:AAD80227".as_ptr(),
+                &mut spayd,
+            );
+            let mut provided = false;
+            assert_eq!(spayd_check_crc32(spayd, &mut provided), SpaydStatus::Ok);
+            assert!(provided);
+
+            spayd_set_field(spayd, c"AM".as_ptr(), c"200.00".as_ptr());
+            assert_eq!(
+                spayd_check_crc32(spayd, &mut provided),
+                SpaydStatus::Crc32Failed
+            );
+
+            spayd_free(spayd);
+        }
+    }
+
+    #[test]
+    fn null_handles() {
+        unsafe {
+            let mut text = null_out();
+            assert_eq!(spayd_validate(ptr::null()), SpaydStatus::NullPointer);
+            assert_eq!(
+                spayd_to_string(ptr::null(), &mut text),
+                SpaydStatus::NullPointer
+            );
+            spayd_free(ptr::null_mut());
+            spayd_string_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn status_messages() {
+        let message = |code| unsafe { CStr::from_ptr(spayd_status_message(code)) };
+        assert_eq!(
+            message(SpaydStatus::FieldMissing as c_int).to_str(),
+            Ok("the field is missing")
+        );
+        assert_eq!(message(100).to_str(), Ok("a required pointer was null"));
+        assert_eq!(message(15).to_str(), Ok("unknown status"));
+        assert_eq!(message(-1).to_str(), Ok("unknown status"));
+    }
+
+    #[test]
+    fn header_is_up_to_date() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/spayd.h"));
+        let checked_in = include_str!("../include/spayd.h");
+        assert_eq!(
+            generated, checked_in,
+            "include/spayd.h is out of date, copy it from the build output"
+        );
+    }
+}
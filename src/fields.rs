@@ -19,3 +19,25 @@ pub const PAYMENT_TYPE: &str = "PT";
 pub const MESSAGE: &str = "MSG";
 /// CRC32 checksum for integrity verification.
 pub const CRC32_CHECKSUM: &str = "CRC32";
+/// Channel for notifying the payee about the payment, P for phone or E for e-mail.
+pub const NOTIFICATION_TYPE: &str = "NT";
+/// Phone number or e-mail address for the payment notification.
+pub const NOTIFICATION_ADDRESS: &str = "NTA";
+/// Number of days to keep retrying the payment if there are insufficient funds.
+pub const RETRY_DAYS: &str = "X-PER";
+/// Czech variable symbol.
+pub const VARIABLE_SYMBOL: &str = "X-VS";
+/// Czech specific symbol.
+pub const SPECIFIC_SYMBOL: &str = "X-SS";
+/// Czech constant symbol.
+pub const CONSTANT_SYMBOL: &str = "X-KS";
+/// Identifier of the payment for the payer.
+pub const PAYER_ID: &str = "X-ID";
+/// URL for custom use.
+pub const URL: &str = "X-URL";
+/// Note of the exchange rate used to convert the amount from another
+/// currency, written by `Spayd::convert_currency`.
+pub const EXCHANGE_RATE: &str = "X-FX";
+
+/// Prefix of fields which are not defined by the standard.
+pub const EXTENSION_PREFIX: &str = "X-";
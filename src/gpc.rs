@@ -0,0 +1,367 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use encoding_rs::WINDOWS_1250;
+use iso_currency::Currency;
+
+use crate::{Direction, Spayd, SpaydError, Transaction};
+
+const FORMAT: &str = "GPC statement";
+
+/// Length of a header or transaction record.
+const RECORD_LENGTH: usize = 128;
+
+/// A Czech bank statement in the fixed-width ABO/GPC format.
+///
+/// Each statement starts with a 074 header record for the account, followed
+/// by a 075 record for each transaction. 078 and 079 records with extra
+/// message lines are added to the message of the transaction before them.
+/// Files are usually encoded in Windows-1250, see `from_bytes`.
+///
+/// Account numbers are written as `prefix-number`, without a bank code as
+/// GPC statements don't include the bank code of the statement's account.
+/// Counterparty accounts include the bank code, e.g. "19-2000145399/0800".
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GpcStatement {
+    account: String,
+    name: String,
+    serial: u32,
+    date: NaiveDate,
+    transactions: Vec<Transaction>,
+}
+
+fn format_error(line: usize, reason: &str) -> SpaydError {
+    SpaydError::FileFormat {
+        format: FORMAT,
+        line,
+        reason: reason.into(),
+    }
+}
+
+/// The characters of a record between 1-based positions, inclusive.
+fn column(record: &[char], start: usize, end: usize) -> String {
+    record[start - 1..end].iter().collect()
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text, "%d%m%y").ok()
+}
+
+/// A 16 digit account number as `prefix-number`, without leading zeros.
+fn format_account(digits: &str) -> Option<String> {
+    let prefix: u64 = digits.get(..6)?.parse().ok()?;
+    let number: u64 = digits.get(6..)?.parse().ok()?;
+    match (prefix, number) {
+        (0, 0) => None,
+        (0, number) => Some(number.to_string()),
+        (prefix, number) => Some(format!("{}-{}", prefix, number)),
+    }
+}
+
+/// A symbol without leading zeros, or `None` if it's zero.
+fn symbol(digits: &str) -> Option<String> {
+    let trimmed = digits.trim().trim_start_matches('0');
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.into())
+    }
+}
+
+fn append_message(transaction: &mut Transaction, text: &str) {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return;
+    }
+    transaction.fill_symbols_from(&text);
+    match &mut transaction.message {
+        Some(message) => {
+            message.push(' ');
+            message.push_str(&text);
+        }
+        None => transaction.message = Some(text),
+    }
+}
+
+/// Parse a 075 transaction record.
+fn parse_transaction(record: &[char]) -> Result<Transaction, &'static str> {
+    let amount = column(record, 49, 60).parse().or(Err("invalid amount"))?;
+    let direction = match record[60] {
+        '1' | '5' => Direction::Debit,
+        '2' | '4' => Direction::Credit,
+        _ => return Err("invalid accounting code"),
+    };
+    let currency = match column(record, 119, 122).trim() {
+        "" | "0000" => Currency::CZK,
+        code => code
+            .parse()
+            .ok()
+            .and_then(Currency::from_numeric)
+            .ok_or("unknown currency")?,
+    };
+    let date = parse_date(&column(record, 123, 128))
+        .or_else(|| parse_date(&column(record, 92, 97)))
+        .ok_or("invalid date")?;
+
+    let bank_code = column(record, 74, 77);
+    let counterparty = format_account(&column(record, 20, 35)).map(|account| {
+        match bank_code.trim_start_matches('0') {
+            "" => account,
+            _ => format!("{}/{}", account, bank_code),
+        }
+    });
+
+    let mut transaction = Transaction {
+        id: symbol(&column(record, 36, 48)),
+        direction,
+        amount,
+        currency: currency.code().into(),
+        date,
+        account: format_account(&column(record, 4, 19)),
+        counterparty,
+        variable_symbol: symbol(&column(record, 62, 71)),
+        specific_symbol: symbol(&column(record, 82, 91)),
+        constant_symbol: symbol(&column(record, 78, 81)),
+        ..Transaction::default()
+    };
+    append_message(&mut transaction, &column(record, 98, 117));
+    Ok(transaction)
+}
+
+impl GpcStatement {
+    /// Read a statement file from disk, decoding it from Windows-1250.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SpaydError> {
+        let bytes = std::fs::read(path).map_err(|e| SpaydError::Io(e.to_string()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Parse a statement encoded in Windows-1250.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpaydError> {
+        let (text, _, _) = WINDOWS_1250.decode(bytes);
+        text.parse()
+    }
+
+    /// Parse a file which may contain statements for several accounts, each
+    /// starting with a 074 header.
+    pub fn parse_all(text: &str) -> Result<Vec<Self>, SpaydError> {
+        let mut statements: Vec<Self> = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut record: Vec<char> = line.chars().collect();
+            if record.len() > RECORD_LENGTH {
+                return Err(format_error(number, "record longer than 128 characters"));
+            }
+            record.resize(RECORD_LENGTH, ' ');
+
+            match column(&record, 1, 3).as_str() {
+                "074" => statements.push(Self {
+                    account: format_account(&column(&record, 4, 19))
+                        .ok_or(format_error(number, "invalid account number"))?,
+                    name: column(&record, 20, 39).trim().into(),
+                    serial: column(&record, 106, 108)
+                        .trim()
+                        .parse()
+                        .or(Err(format_error(number, "invalid statement number")))?,
+                    date: parse_date(&column(&record, 109, 114))
+                        .ok_or(format_error(number, "invalid date"))?,
+                    transactions: Vec::new(),
+                }),
+                "075" => {
+                    let statement = statements
+                        .last_mut()
+                        .ok_or(format_error(number, "transaction before the 074 header"))?;
+                    let transaction = parse_transaction(&record)
+                        .map_err(|reason| format_error(number, reason))?;
+                    statement.transactions.push(transaction);
+                }
+                "076" => {}
+                "078" | "079" => {
+                    let transaction = statements
+                        .last_mut()
+                        .and_then(|statement| statement.transactions.last_mut())
+                        .ok_or(format_error(number, "message without a transaction"))?;
+                    append_message(transaction, &column(&record, 4, RECORD_LENGTH));
+                }
+                _ => return Err(format_error(number, "unknown record type")),
+            }
+        }
+
+        Ok(statements)
+    }
+
+    /// The statement's account as `prefix-number`.
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// The account name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The statement number.
+    pub fn serial(&self) -> u32 {
+        self.serial
+    }
+
+    /// The date of the statement.
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// The transactions in the order they appear.
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// Take the transactions.
+    pub fn into_transactions(self) -> Vec<Transaction> {
+        self.transactions
+    }
+
+    /// For each transaction, find the first issued payment whose ACC, AM,
+    /// CC and X-VS it satisfies (see `Transaction::satisfies`).
+    pub fn match_issued(&self, issued: &[Spayd]) -> Vec<Option<usize>> {
+        self.transactions
+            .iter()
+            .map(|transaction| issued.iter().position(|p| transaction.satisfies(p)))
+            .collect()
+    }
+}
+
+impl FromStr for GpcStatement {
+    type Err = SpaydError;
+
+    /// Parse a statement for one account. Use `parse_all` for files with
+    /// several statements.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut statements = Self::parse_all(text)?;
+        match statements.len() {
+            0 => Err(format_error(1, "no 074 header")),
+            1 => Ok(statements.remove(0)),
+            _ => Err(format_error(1, "more than one statement")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATEMENT: &str = "\
+0740000001265098001MUJ UCET            15092600000001000000+00000001080000+000000000000000000000000800000001161026              \r
+0750000001265098001000019200014539900000000000010000000500002000000100100080003080000000022161026Jan Novák           00203161026\r
+078Faktura 2026-00123\r
+0750000001265098001000000000000000000000000000020000000300001000000000000000000000000000000161026Poplatek            00203161026\r
+";
+
+    fn issued(vs: &str, amount: &str) -> Spayd {
+        Spayd::new_v1_0(vec![
+            ("ACC", "CZ5855000000001265098001"),
+            ("AM", amount),
+            ("CC", "CZK"),
+            ("X-VS", vs),
+        ])
+    }
+
+    #[test]
+    fn parse() {
+        let statement: GpcStatement = STATEMENT.parse().unwrap();
+        assert_eq!(statement.account(), "1265098001");
+        assert_eq!(statement.name(), "MUJ UCET");
+        assert_eq!(statement.serial(), 1);
+        assert_eq!(
+            statement.date(),
+            NaiveDate::from_ymd_opt(2026, 10, 16).unwrap()
+        );
+
+        let transactions = statement.transactions();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(
+            transactions[0],
+            Transaction {
+                id: Some("1".into()),
+                direction: Direction::Credit,
+                amount: 50_000,
+                currency: "CZK".into(),
+                date: NaiveDate::from_ymd_opt(2026, 10, 16).unwrap(),
+                account: Some("1265098001".into()),
+                counterparty: Some("19-2000145399/0800".into()),
+                variable_symbol: Some("1001".into()),
+                specific_symbol: Some("22".into()),
+                constant_symbol: Some("308".into()),
+                reference: None,
+                message: Some("Jan Novák Faktura 2026-00123".into()),
+            }
+        );
+        assert_eq!(transactions[1].direction, Direction::Debit);
+        assert_eq!(transactions[1].counterparty, None);
+        assert_eq!(transactions[1].variable_symbol, None);
+    }
+
+    #[test]
+    fn large_amount() {
+        let text = STATEMENT.replace("000000050000", "001250000000");
+        let statement: GpcStatement = text.parse().unwrap();
+        assert_eq!(statement.transactions()[0].amount, 1_250_000_000);
+    }
+
+    #[test]
+    fn windows_1250() {
+        let (bytes, _, _) = WINDOWS_1250.encode(STATEMENT);
+        assert_eq!(bytes[..].iter().filter(|b| **b == 0xE1).count(), 1);
+
+        let statement = GpcStatement::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            statement.transactions()[0].message.as_deref(),
+            Some("Jan Novák Faktura 2026-00123")
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let body = STATEMENT.lines().nth(1).unwrap();
+        assert_eq!(
+            body.parse::<GpcStatement>(),
+            Err(format_error(1, "transaction before the 074 header"))
+        );
+        assert_eq!(
+            format!("{}999", STATEMENT).parse::<GpcStatement>(),
+            Err(format_error(5, "unknown record type"))
+        );
+        assert_eq!(
+            STATEMENT
+                .replace("22161026Jan", "22XXXXXXJan")
+                .replace("00203161026\r\n078", "00203XXXXXX\r\n078")
+                .parse::<GpcStatement>(),
+            Err(format_error(2, "invalid date"))
+        );
+        assert_eq!(
+            format!("{0}{0}", STATEMENT).parse::<GpcStatement>(),
+            Err(format_error(1, "more than one statement"))
+        );
+        assert_eq!(
+            GpcStatement::parse_all(&format!("{0}{0}", STATEMENT))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn match_issued_payments() {
+        let statement: GpcStatement = STATEMENT.parse().unwrap();
+        let payments = [issued("1002", "500.00"), issued("1001", "500.00")];
+        assert_eq!(statement.match_issued(&payments), vec![Some(1), None]);
+
+        let wrong_amount = [issued("1001", "400.00")];
+        assert_eq!(statement.match_issued(&wrong_amount), vec![None, None]);
+    }
+}
@@ -43,6 +43,7 @@ impl FromStr for IbanBic {
     }
 }
 
+#[cfg(feature = "iban_validate")]
 impl From<Iban> for IbanBic {
     fn from(iban: Iban) -> Self {
         Self::iban_only(iban)
@@ -62,6 +63,7 @@ impl IbanBic {
     }
 
     /// Construct and IbanBic with both an IBAN and a BIC
+    #[allow(clippy::self_named_constructors)]
     pub fn iban_bic<T, U>(iban: T, bic: U) -> Self
     where
         T: ToString,
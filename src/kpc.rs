@@ -0,0 +1,372 @@
+use chrono::NaiveDate;
+
+use crate::{fields, DomesticAccount, Spayd, SpaydError};
+
+const FORMAT: &str = "KPC";
+
+/// Accounting file type for domestic payment orders.
+const PAYMENT_ORDERS: &str = "1501";
+
+/// Maximum number of digits in the variable and specific symbols.
+const MAX_SYMBOL_LENGTH: usize = 10;
+
+/// Maximum number of digits in the constant symbol.
+const MAX_CONSTANT_SYMBOL_LENGTH: usize = 4;
+
+/// Maximum length of the message for the recipient (`AV:`).
+const MAX_MESSAGE_LENGTH: usize = 140;
+
+/// Largest serial number which fits the three digits of the file number.
+const MAX_SERIAL: u16 = 999;
+
+/// A batch of domestic payment orders from one account, exported in the
+/// Czech ABO format used by KPC files.
+///
+/// Payments are grouped by due date, taken from DT or the batch's date.
+/// ACC must be a Czech IBAN, which is written as a domestic account number,
+/// and the currency must be CZK. X-VS, X-KS, X-SS and MSG are written as the
+/// payment's symbols and message for the recipient.
+/// ```
+/// use chrono::NaiveDate;
+/// use spayd::{KpcBatch, Spayd};
+///
+/// let date = NaiveDate::from_ymd_opt(2026, 10, 20).unwrap();
+/// let account = "1265098001/5500".parse().unwrap();
+/// let mut batch = KpcBatch::new("FIRMA SRO", account, date);
+/// batch.push(Spayd::new_v1_0(vec![
+///     ("ACC", "CZ6508000000192000145399"),
+///     ("AM", "500.00"),
+///     ("X-VS", "1001"),
+/// ]));
+///
+/// let kpc = batch.to_kpc(1, date).unwrap();
+/// assert!(kpc.contains("000019-2000145399 000000050000 0000001001 08000000 0000000000 AV:"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KpcBatch {
+    client_name: String,
+    account: DomesticAccount,
+    due_date: NaiveDate,
+    payments: Vec<Spayd>,
+}
+
+fn export_error(index: usize, reason: String) -> SpaydError {
+    SpaydError::ExportError {
+        format: FORMAT,
+        index,
+        reason,
+    }
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%d%m%y").to_string()
+}
+
+fn format_account(account: &DomesticAccount) -> String {
+    format!("{:06}-{:010}", account.prefix(), account.number())
+}
+
+/// A payment converted to the values of a KPC item.
+struct Order {
+    account: DomesticAccount,
+    amount: u64,
+    date: NaiveDate,
+    variable_symbol: u64,
+    constant_symbol: u64,
+    specific_symbol: u64,
+    message: String,
+}
+
+/// A symbol field as a number, zero if it's missing.
+fn symbol(payment: &Spayd, field: &str, max_length: usize) -> Result<u64, String> {
+    match payment.field(field) {
+        None => Ok(0),
+        Some(text)
+            if (1..=max_length).contains(&text.len())
+                && text.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            text.parse()
+                .map_err(|_| format!("invalid {} '{}'", field, text))
+        }
+        Some(text) => Err(format!(
+            "{} '{}' isn't 1 to {} digits",
+            field, text, max_length
+        )),
+    }
+}
+
+impl Order {
+    fn new(payment: &Spayd, default_date: NaiveDate) -> Result<Self, String> {
+        if let Some(currency) = payment.field(fields::CURRENCY) {
+            if currency != "CZK" {
+                return Err(format!(
+                    "currency {} isn't supported, only CZK payments can be exported",
+                    currency
+                ));
+            }
+        }
+
+        let iban = payment.account().map_err(|e| e.to_string())?.iban;
+        if !iban.to_ascii_uppercase().starts_with("CZ") {
+            return Err(format!("account {} isn't a Czech account", iban));
+        }
+        let account = DomesticAccount::from_iban(&iban)
+            .map_err(|_| format!("account {} isn't a valid Czech IBAN", iban))?;
+
+        let message = payment
+            .field(fields::MESSAGE)
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if message.chars().count() > MAX_MESSAGE_LENGTH {
+            return Err(format!(
+                "MSG is longer than {} characters",
+                MAX_MESSAGE_LENGTH
+            ));
+        }
+
+        let date = match payment.field(fields::DUE_DATE) {
+            Some(_) => payment.due_date().map_err(|e| e.to_string())?,
+            None => default_date,
+        };
+        Ok(Self {
+            account,
+            amount: payment
+                .fixed_amount()
+                .map_err(|e| e.to_string())?
+                .minor_units(),
+            date,
+            variable_symbol: symbol(payment, fields::VARIABLE_SYMBOL, MAX_SYMBOL_LENGTH)?,
+            constant_symbol: symbol(payment, fields::CONSTANT_SYMBOL, MAX_CONSTANT_SYMBOL_LENGTH)?,
+            specific_symbol: symbol(payment, fields::SPECIFIC_SYMBOL, MAX_SYMBOL_LENGTH)?,
+            message,
+        })
+    }
+
+    fn line(&self) -> String {
+        format!(
+            "{} {:012} {:010} {:04}{:04} {:010} AV:{}",
+            format_account(&self.account),
+            self.amount,
+            self.variable_symbol,
+            self.account.bank_code(),
+            self.constant_symbol,
+            self.specific_symbol,
+            self.message
+        )
+    }
+}
+
+impl KpcBatch {
+    /// Create an empty batch paid from the client's account, due on the
+    /// given date unless a payment has its own DT. The name is written in
+    /// the file header.
+    pub fn new(client_name: &str, account: DomesticAccount, due_date: NaiveDate) -> Self {
+        Self {
+            client_name: client_name.into(),
+            account,
+            due_date,
+            payments: Vec::new(),
+        }
+    }
+
+    /// Add a payment to the batch.
+    pub fn push(&mut self, payment: Spayd) {
+        self.payments.push(payment);
+    }
+
+    /// The client's name.
+    pub fn client_name(&self) -> &str {
+        &self.client_name
+    }
+
+    /// The account the payments are made from.
+    pub fn account(&self) -> DomesticAccount {
+        self.account
+    }
+
+    /// The due date for payments without DT.
+    pub fn due_date(&self) -> NaiveDate {
+        self.due_date
+    }
+
+    /// The payments in the batch.
+    pub fn payments(&self) -> &[Spayd] {
+        &self.payments
+    }
+
+    /// Write the batch as a KPC file with CRLF line endings. The serial
+    /// number distinguishes files sent on the same day, and `created` is the
+    /// date in the header.
+    ///
+    /// Fails with `ExportError` if a payment isn't in CZK, its ACC isn't a
+    /// valid Czech IBAN, it has no AM or a symbol or the message is too long,
+    /// and also (with index 0) if the serial number is more than 999.
+    /// The text isn't transliterated, so banks which only accept ASCII need
+    /// a message without diacritics.
+    pub fn to_kpc(&self, serial: u16, created: NaiveDate) -> Result<String, SpaydError> {
+        if serial > MAX_SERIAL {
+            return Err(export_error(
+                0,
+                format!("serial number {} is more than {}", serial, MAX_SERIAL),
+            ));
+        }
+        let orders = self
+            .payments
+            .iter()
+            .enumerate()
+            .map(|(index, payment)| {
+                Order::new(payment, self.due_date).map_err(|reason| export_error(index, reason))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut dates: Vec<NaiveDate> = orders.iter().map(|o| o.date).collect();
+        dates.sort();
+        dates.dedup();
+
+        let name: String = self.client_name.chars().take(20).collect();
+        let mut lines = vec![
+            format!(
+                "UHL1{}{:<20}{:010}001999000000000000",
+                format_date(created),
+                name,
+                self.account.number()
+            ),
+            format!(
+                "1 {} {:03}000 {:04}",
+                PAYMENT_ORDERS,
+                serial,
+                self.account.bank_code()
+            ),
+        ];
+        for date in dates {
+            let group: Vec<&Order> = orders.iter().filter(|o| o.date == date).collect();
+            let total: u64 = group.iter().map(|o| o.amount).sum();
+            lines.push(format!(
+                "2 {} {:014} {}",
+                format_account(&self.account),
+                total,
+                format_date(date)
+            ));
+            lines.extend(group.iter().map(|order| order.line()));
+            lines.push("3 +".into());
+        }
+        lines.push("5 +".into());
+
+        let mut output = lines.join("\r\n");
+        output.push_str("\r\n");
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn batch() -> KpcBatch {
+        let mut batch = KpcBatch::new(
+            "FIRMA A SYN SRO",
+            "1265098001/5500".parse().unwrap(),
+            date(20),
+        );
+        batch.push(Spayd::new_v1_0(vec![
+            ("ACC", "CZ6508000000192000145399+GIBACZPX"),
+            ("AM", "500.00"),
+            ("CC", "CZK"),
+            ("MSG", "Faktura 2026-00123"),
+            ("X-VS", "1001"),
+            ("X-KS", "308"),
+            ("X-SS", "22"),
+        ]));
+        batch.push(Spayd::new_v1_0(vec![
+            ("ACC", "CZ5855000000001265098001"),
+            ("AM", "12.5"),
+            ("DT", "20261022"),
+        ]));
+        batch.push(Spayd::new_v1_0(vec![
+            ("ACC", "CZ6508000000192000145399"),
+            ("AM", "1000"),
+        ]));
+        batch
+    }
+
+    #[test]
+    fn export() {
+        let kpc = batch().to_kpc(7, date(19)).unwrap();
+        assert_eq!(
+            kpc,
+            "UHL1191026FIRMA A SYN SRO     1265098001001999000000000000\r\n\
+             1 1501 007000 5500\r\n\
+             2 000000-1265098001 00000000150000 201026\r\n\
+             000019-2000145399 000000050000 0000001001 08000308 0000000022 AV:Faktura 2026-00123\r\n\
+             000019-2000145399 000000100000 0000000000 08000000 0000000000 AV:\r\n\
+             3 +\r\n\
+             2 000000-1265098001 00000000001250 221026\r\n\
+             000000-1265098001 000000001250 0000000000 55000000 0000000000 AV:\r\n\
+             3 +\r\n\
+             5 +\r\n"
+        );
+    }
+
+    #[test]
+    fn rejected_payments() {
+        let rejected = |fields: Vec<(&str, &str)>| {
+            let mut batch = batch();
+            batch.push(Spayd::new_v1_0(fields));
+            batch.to_kpc(1, date(19))
+        };
+        let error = |reason: &str| {
+            Err(SpaydError::ExportError {
+                format: FORMAT,
+                index: 3,
+                reason: reason.into(),
+            })
+        };
+
+        assert_eq!(
+            rejected(vec![
+                ("ACC", "CZ6508000000192000145399"),
+                ("AM", "10"),
+                ("CC", "EUR"),
+            ]),
+            error("currency EUR isn't supported, only CZK payments can be exported")
+        );
+        assert_eq!(
+            rejected(vec![("ACC", "SK3112000000198742637541"), ("AM", "10")]),
+            error("account SK3112000000198742637541 isn't a Czech account")
+        );
+        assert_eq!(
+            rejected(vec![("ACC", "CZ6508000000192000145398"), ("AM", "10")]),
+            error("account CZ6508000000192000145398 isn't a valid Czech IBAN")
+        );
+        assert_eq!(
+            rejected(vec![("ACC", "CZ6508000000192000145399")]),
+            error("field 'AM' is missing")
+        );
+        assert_eq!(
+            rejected(vec![
+                ("ACC", "CZ6508000000192000145399"),
+                ("AM", "10"),
+                ("X-KS", "12345"),
+            ]),
+            error("X-KS '12345' isn't 1 to 4 digits")
+        );
+    }
+
+    #[test]
+    fn serial_limit() {
+        assert!(batch().to_kpc(999, date(19)).is_ok());
+        assert_eq!(
+            batch().to_kpc(1000, date(19)),
+            Err(SpaydError::ExportError {
+                format: FORMAT,
+                index: 0,
+                reason: "serial number 1000 is more than 999".into(),
+            })
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use crate::reference::mod97;
+use crate::{Spayd, SpaydError};
+
+/// The start of every SPAYD, as sent by the scanner.
+const HEADER: &str = "SPD*";
+
+/// Pairs of a US character and the character typed by the same key.
+type Keys = &'static [(char, char)];
+
+/// Keys of the number row and punctuation on Czech QWERTZ and QWERTY,
+/// as (US character, Czech character) pairs.
+const CZECH_KEYS: Keys = &[
+    ('`', ';'),
+    ('~', '°'),
+    ('1', '+'),
+    ('2', 'ě'),
+    ('3', 'š'),
+    ('4', 'č'),
+    ('5', 'ř'),
+    ('6', 'ž'),
+    ('7', 'ý'),
+    ('8', 'á'),
+    ('9', 'í'),
+    ('0', 'é'),
+    ('-', '='),
+    ('=', '´'),
+    ('!', '1'),
+    ('@', '2'),
+    ('#', '3'),
+    ('$', '4'),
+    ('%', '5'),
+    ('^', '6'),
+    ('&', '7'),
+    ('*', '8'),
+    ('(', '9'),
+    (')', '0'),
+    ('_', '%'),
+    ('+', 'ˇ'),
+    ('[', 'ú'),
+    (']', ')'),
+    ('{', '/'),
+    ('}', '('),
+    (';', 'ů'),
+    ('\'', '§'),
+    (':', '"'),
+    ('"', '!'),
+    ('\\', '¨'),
+    ('|', '\''),
+    ('/', '-'),
+    ('<', '?'),
+    ('>', ':'),
+    ('?', '_'),
+];
+
+/// Keys of the number row and punctuation on Slovak QWERTZ.
+const SLOVAK_KEYS: Keys = &[
+    ('`', ';'),
+    ('~', '°'),
+    ('1', '+'),
+    ('2', 'ľ'),
+    ('3', 'š'),
+    ('4', 'č'),
+    ('5', 'ť'),
+    ('6', 'ž'),
+    ('7', 'ý'),
+    ('8', 'á'),
+    ('9', 'í'),
+    ('0', 'é'),
+    ('-', '='),
+    ('=', '´'),
+    ('!', '1'),
+    ('@', '2'),
+    ('#', '3'),
+    ('$', '4'),
+    ('%', '5'),
+    ('^', '6'),
+    ('&', '7'),
+    ('*', '8'),
+    ('(', '9'),
+    (')', '0'),
+    ('_', '%'),
+    ('+', 'ˇ'),
+    ('[', 'ú'),
+    (']', 'ä'),
+    ('{', '/'),
+    ('}', '('),
+    (';', 'ô'),
+    ('\'', '§'),
+    (':', '"'),
+    ('"', '!'),
+    ('\\', 'ň'),
+    ('|', ')'),
+    ('/', '-'),
+    ('<', '?'),
+    ('>', ':'),
+    ('?', '_'),
+];
+
+/// Keys of the number row and punctuation on German QWERTZ.
+const GERMAN_KEYS: Keys = &[
+    ('`', '^'),
+    ('~', '°'),
+    ('@', '"'),
+    ('#', '§'),
+    ('^', '&'),
+    ('&', '/'),
+    ('*', '('),
+    ('(', ')'),
+    (')', '='),
+    ('-', 'ß'),
+    ('=', '´'),
+    ('_', '?'),
+    ('+', '`'),
+    ('[', 'ü'),
+    (']', '+'),
+    ('{', 'Ü'),
+    ('}', '*'),
+    (';', 'ö'),
+    ('\'', 'ä'),
+    (':', 'Ö'),
+    ('"', 'Ä'),
+    ('\\', '#'),
+    ('|', '\''),
+    ('/', '-'),
+    ('<', ';'),
+    ('>', ':'),
+    ('?', '_'),
+];
+
+/// The swapped letters of QWERTZ layouts.
+const QWERTZ_KEYS: Keys = &[('y', 'z'), ('z', 'y'), ('Y', 'Z'), ('Z', 'Y')];
+
+/// The keyboard layout of the computer a barcode scanner is connected to.
+///
+/// Scanners in keyboard wedge mode usually type the code as key presses on
+/// a US keyboard. If the computer uses another layout, the keys produce
+/// other characters, e.g. "SPD*1.0*ACC:CZ" arrives as `SPD8+.é8ACC"CY` on
+/// Czech QWERTZ. Dead keys are assumed to produce their own character, such
+/// as `ˇ` for `+` on Czech layouts.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KeyboardLayout {
+    /// US English, which needs no conversion.
+    Us,
+    /// Czech QWERTZ, the default Czech layout.
+    CzechQwertz,
+    /// Czech QWERTY.
+    CzechQwerty,
+    /// Slovak QWERTZ.
+    Slovak,
+    /// German QWERTZ.
+    German,
+}
+
+/// The layouts in the order they're preferred when detection is ambiguous.
+const LAYOUTS: [KeyboardLayout; 5] = [
+    KeyboardLayout::Us,
+    KeyboardLayout::CzechQwertz,
+    KeyboardLayout::CzechQwerty,
+    KeyboardLayout::Slovak,
+    KeyboardLayout::German,
+];
+
+/// Check that the SPAYD's account has correct IBAN check digits.
+fn has_valid_iban(spayd: &Spayd) -> bool {
+    let Ok(account) = spayd.account() else {
+        return false;
+    };
+    let iban = account.iban;
+    iban.len() > 4 && mod97(&format!("{}{}", &iban[4..], &iban[..4])) == Some(1)
+}
+
+impl KeyboardLayout {
+    fn keys(&self) -> impl Iterator<Item = &'static (char, char)> {
+        let (keys, swapped): (Keys, Keys) = match self {
+            Self::Us => (&[], &[]),
+            Self::CzechQwertz => (CZECH_KEYS, QWERTZ_KEYS),
+            Self::CzechQwerty => (CZECH_KEYS, &[]),
+            Self::Slovak => (SLOVAK_KEYS, QWERTZ_KEYS),
+            Self::German => (GERMAN_KEYS, QWERTZ_KEYS),
+        };
+        keys.iter().chain(swapped)
+    }
+
+    /// The text produced by typing US text on this layout.
+    pub fn garble(&self, text: &str) -> String {
+        text.chars()
+            .map(|c| {
+                self.keys()
+                    .find(|(us, _)| *us == c)
+                    .map_or(c, |(_, typed)| *typed)
+            })
+            .collect()
+    }
+
+    /// The US text which was typed to produce text on this layout.
+    pub fn degarble(&self, text: &str) -> String {
+        text.chars()
+            .map(|c| {
+                self.keys()
+                    .find(|(_, typed)| *typed == c)
+                    .map_or(c, |(us, _)| *us)
+            })
+            .collect()
+    }
+
+    /// Detect the layout a SPAYD was typed on from its `SPD*` header.
+    ///
+    /// If the header matches several layouts, the first one in the order
+    /// US, Czech QWERTZ, Czech QWERTY, Slovak, German is chosen whose
+    /// converted text is a valid SPAYD with a correct IBAN, or failing that
+    /// any valid SPAYD. Returns `None` if the header doesn't match any
+    /// layout.
+    pub fn detect(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let candidates: Vec<(Self, Option<Spayd>)> = LAYOUTS
+            .into_iter()
+            .filter(|layout| text.starts_with(&layout.garble(HEADER)))
+            .map(|layout| (layout, layout.degarble(text).parse().ok()))
+            .collect();
+
+        let with_iban = candidates
+            .iter()
+            .find(|(_, spayd)| spayd.as_ref().is_some_and(has_valid_iban));
+        let valid = candidates.iter().find(|(_, spayd)| spayd.is_some());
+        with_iban
+            .or(valid)
+            .or(candidates.first())
+            .map(|(layout, _)| *layout)
+    }
+}
+
+/// Parse a SPAYD typed by a barcode scanner, converting it from the
+/// detected keyboard layout first. Returns the layout along with the
+/// SPAYD. Fails like parsing the unconverted text if no layout is detected.
+/// ```
+/// use spayd::{parse_degarbled, KeyboardLayout};
+///
+/// let (spayd, layout) = parse_degarbled("SPD8+.é8ACC\"CYřářřéééééééé+ěžřéíáéé+").unwrap();
+/// assert_eq!(layout, KeyboardLayout::CzechQwertz);
+/// assert_eq!(spayd.field("ACC"), Some("CZ5855000000001265098001"));
+/// ```
+pub fn parse_degarbled(text: &str) -> Result<(Spayd, KeyboardLayout), SpaydError> {
+    let text = text.trim();
+    match KeyboardLayout::detect(text) {
+        Some(layout) => Ok((layout.degarble(text).parse()?, layout)),
+        None => Ok((text.parse()?, KeyboardLayout::Us)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "SPD*1.0*ACC:CZ5855000000001265098001+RZBCCZPP*AM:480.50*CC:CZK*MSG:Zaplaceno dne 2.1.*X-VS:1234567890";
+
+    #[test]
+    fn garble() {
+        assert_eq!(
+            KeyboardLayout::CzechQwertz.garble("SPD*1.0*ACC:CZ"),
+            "SPD8+.é8ACC\"CY"
+        );
+        assert_eq!(
+            KeyboardLayout::CzechQwerty.garble("SPD*1.0*ACC:CZ"),
+            "SPD8+.é8ACC\"CZ"
+        );
+        assert_eq!(KeyboardLayout::Slovak.garble("SPD*1.2"), "SPD8+.ľ");
+        assert_eq!(
+            KeyboardLayout::German.garble("SPD*1.0*ACC:CZ"),
+            "SPD(1.0(ACCÖCY"
+        );
+        assert_eq!(KeyboardLayout::Us.garble(CODE), CODE);
+    }
+
+    #[test]
+    fn round_trip() {
+        for layout in LAYOUTS {
+            assert_eq!(layout.degarble(&layout.garble(CODE)), CODE, "{layout:?}");
+        }
+    }
+
+    #[test]
+    fn detect_and_parse() {
+        let expected: Spayd = CODE.parse().unwrap();
+        for layout in LAYOUTS {
+            let garbled = format!("{}\r\n", layout.garble(CODE));
+            assert_eq!(KeyboardLayout::detect(&garbled), Some(layout), "{layout:?}");
+            assert_eq!(parse_degarbled(&garbled), Ok((expected.clone(), layout)));
+        }
+    }
+
+    #[test]
+    fn prefers_valid_iban() {
+        let code = "SPD*1.0*ACC:CZ5855000000001265098001*AM:5";
+        let qwerty = KeyboardLayout::CzechQwerty.garble(code);
+        assert_eq!(
+            KeyboardLayout::detect(&qwerty),
+            Some(KeyboardLayout::CzechQwerty)
+        );
+
+        let slovak = KeyboardLayout::Slovak.garble(code);
+        assert_eq!(
+            KeyboardLayout::detect(&slovak),
+            Some(KeyboardLayout::Slovak)
+        );
+    }
+
+    #[test]
+    fn not_a_spayd() {
+        assert_eq!(KeyboardLayout::detect("hello"), None);
+        assert!(parse_degarbled("hello").is_err());
+        assert_eq!(
+            KeyboardLayout::detect("SPD8+.é8AM\"+"),
+            Some(KeyboardLayout::CzechQwertz)
+        );
+        assert_eq!(
+            parse_degarbled("SPD8+.é8AM\"+"),
+            Err(SpaydError::RequiredFieldMissing("ACC".into()))
+        );
+    }
+}
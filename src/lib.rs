@@ -57,19 +57,96 @@
 //! ```
 //!
 
+mod amount;
+mod batch;
+#[cfg(feature = "chrono")]
+mod calendar;
+#[cfg(feature = "camt")]
+mod camt;
+#[cfg(all(feature = "chrono", feature = "rust_decimal", feature = "iso_currency"))]
+mod cnb;
 mod convert;
 #[cfg(feature = "crc32")]
 mod crc32;
+#[cfg(feature = "csv")]
+mod csv;
+mod custom;
+mod domestic;
 mod error;
+/// C ABI for embedding the library in non-Rust applications.
+#[cfg(feature = "ffi")]
+pub mod ffi;
 /// Constants for the standard field names.
 pub mod fields;
+#[cfg(feature = "gpc")]
+mod gpc;
 mod iban_bic;
+#[cfg(feature = "kpc")]
+mod kpc;
+mod layout;
+#[cfg(feature = "mt940")]
+mod mt940;
+#[cfg(feature = "pain")]
+mod pain;
 mod parser;
+/// Python bindings built with PyO3.
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "chrono")]
+mod reconcile;
+mod reference;
+#[cfg(feature = "crc32")]
+mod repair;
+#[cfg(all(feature = "rust_decimal", feature = "iso_currency"))]
+mod rounding;
+mod scan;
+mod schema;
 mod spayd;
+mod stream;
+mod symbol;
+/// WebAssembly bindings for use from JavaScript.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(any(feature = "camt", feature = "pain"))]
+mod xml;
 
-pub use crate::convert::*;
+pub use crate::amount::Amount;
+pub use crate::batch::{BatchLine, BatchParser, BatchReport, BatchSummary};
+#[cfg(feature = "chrono")]
+pub use crate::calendar::{easter_sunday, BusinessDayRule, HolidayCalendar};
+#[cfg(feature = "camt")]
+pub use crate::camt::{CamtMessage, CamtStatement};
+#[cfg(all(feature = "chrono", feature = "rust_decimal", feature = "iso_currency"))]
+pub use crate::cnb::{CurrencyConversion, ExchangeRate, ExchangeRates};
 #[cfg(feature = "crc32")]
 pub use crate::crc32::{Crc32Ok, Crc32Result};
+#[cfg(feature = "csv")]
+pub use crate::csv::CsvMapping;
+pub use crate::custom::{CustomField, FieldRegistry, FieldValidator};
+pub use crate::domestic::{same_account, DomesticAccount};
 pub use crate::error::SpaydError;
+#[cfg(feature = "gpc")]
+pub use crate::gpc::GpcStatement;
 pub use crate::iban_bic::*;
+#[cfg(feature = "kpc")]
+pub use crate::kpc::KpcBatch;
+pub use crate::layout::{parse_degarbled, KeyboardLayout};
+#[cfg(feature = "mt940")]
+pub use crate::mt940::Mt940Statement;
+#[cfg(feature = "pain")]
+pub use crate::pain::{PainVersion, PaymentBatch};
+#[cfg(feature = "chrono")]
+pub use crate::reconcile::{
+    reconcile, Direction, Match, MatchKind, Reconciler, Reconciliation, Settlement,
+    SettlementStatus, Transaction,
+};
+pub use crate::reference::{check_spayd_reference, CreditorReference};
+#[cfg(feature = "crc32")]
+pub use crate::repair::{Crc32Repair, Crc32Repairer, Substitution};
+#[cfg(all(feature = "rust_decimal", feature = "iso_currency"))]
+pub use crate::rounding::{currency_decimals, round_amount, RoundingMode};
+pub use crate::scan::{find_spayds, SpaydMatch};
+pub use crate::schema::{SpaydSchema, SpaydWarning};
 pub use crate::spayd::*;
+pub use crate::stream::{SpaydStreamParser, StreamStatus};
+pub use crate::symbol::{CheckDigit, VariableSymbolGenerator};
@@ -0,0 +1,440 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::amount::parse_minor_units;
+use crate::{Direction, SpaydError, Transaction};
+
+const FORMAT: &str = "MT940 statement";
+
+/// Customer reference used when there isn't one.
+const NO_REFERENCE: &str = "NONREF";
+
+/// A SWIFT MT940 customer statement.
+///
+/// The tags :20: (reference), :25: (account), :28C: (statement number),
+/// :60F:/:60M: (opening balance, for the currency), :61: (statement line),
+/// :86: (information for the line before) and :62F:/:62M: are read. Other
+/// tags and SWIFT block headers such as `{1:...}` are skipped.
+///
+/// The :86: field may use `?NN` subfields, where ?20 to ?29 and ?60 to ?63
+/// hold the remittance information, ?30 the counterparty's bank code, ?31
+/// its account and "EREF+" marks an end-to-end reference. Czech symbols are
+/// found in the remittance information, e.g. "/VS/1001/SS/22/KS/0308". Use
+/// `Transaction::satisfies` to check transactions against issued payments.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Mt940Statement {
+    reference: String,
+    account: String,
+    number: String,
+    transactions: Vec<Transaction>,
+}
+
+/// A tag and its value, which may span several lines.
+struct Field<'a> {
+    line: usize,
+    tag: &'a str,
+    value: String,
+}
+
+fn format_error(line: usize, reason: &str) -> SpaydError {
+    SpaydError::FileFormat {
+        format: FORMAT,
+        line,
+        reason: reason.into(),
+    }
+}
+
+/// Split the text into tags, skipping block headers and trailers.
+fn fields(text: &str) -> Result<Vec<Field<'_>>, SpaydError> {
+    let mut fields: Vec<Field> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('{') || line.starts_with("-}") || line == "-" {
+            continue;
+        }
+
+        let tag = line
+            .strip_prefix(':')
+            .and_then(|rest| rest.split_once(':'))
+            .filter(|(tag, _)| (2..=3).contains(&tag.len()));
+        match (tag, fields.last_mut()) {
+            (Some((tag, value)), _) => fields.push(Field {
+                line: index + 1,
+                tag,
+                value: value.into(),
+            }),
+            (None, Some(field)) => {
+                field.value.push('\n');
+                field.value.push_str(line);
+            }
+            (None, None) => return Err(format_error(index + 1, "expected a tag")),
+        }
+    }
+    Ok(fields)
+}
+
+/// Parse a date in YYMMDD format.
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.get(..6)?, "%y%m%d").ok()
+}
+
+/// Parse an amount with a decimal comma, e.g. "1000,5" or "1000,", into
+/// minor units.
+fn parse_amount(text: &str) -> Option<u64> {
+    let text = text.strip_suffix(',').unwrap_or(text);
+    parse_minor_units(&text.replace(',', "."))
+}
+
+/// Write "bank/account" accounts as "account/bank" to match Czech domestic
+/// numbers.
+fn normalize_account(account: &str) -> String {
+    match account.split_once('/') {
+        Some((bank, number))
+            if bank.len() == 4 && bank.bytes().all(|b| b.is_ascii_digit()) && number.len() > 4 =>
+        {
+            format!("{}/{}", number, bank)
+        }
+        _ => account.into(),
+    }
+}
+
+/// Read the currency from an opening balance, e.g. "C261015CZK1000,00".
+fn balance_currency(value: &str) -> Option<String> {
+    let currency = value.get(7..10)?;
+    if currency.bytes().all(|b| b.is_ascii_uppercase()) {
+        Some(currency.into())
+    } else {
+        None
+    }
+}
+
+/// Parse a :61: statement line.
+fn parse_line(value: &str, currency: &str, account: &str) -> Result<Transaction, &'static str> {
+    let (first, _) = value.split_once('\n').unwrap_or((value, ""));
+    let value_date = parse_date(first).ok_or("invalid value date")?;
+    let mut rest = &first[6..];
+
+    // Optional booking date as MMDD, in the year closest to the value date
+    let mut date = value_date;
+    if rest.len() >= 4 && rest.as_bytes()[..4].iter().all(u8::is_ascii_digit) {
+        let month = rest[..2].parse().or(Err("invalid booking date"))?;
+        let day = rest[2..4].parse().or(Err("invalid booking date"))?;
+        let year = match (value_date.month(), month) {
+            (12, 1) => value_date.year() + 1,
+            (1, 12) => value_date.year() - 1,
+            _ => value_date.year(),
+        };
+        date = NaiveDate::from_ymd_opt(year, month, day).ok_or("invalid booking date")?;
+        rest = &rest[4..];
+    }
+
+    let (direction, after_mark) = if let Some(after) = rest.strip_prefix("RC") {
+        (Direction::Debit, after)
+    } else if let Some(after) = rest.strip_prefix("RD") {
+        (Direction::Credit, after)
+    } else if let Some(after) = rest.strip_prefix('C') {
+        (Direction::Credit, after)
+    } else if let Some(after) = rest.strip_prefix('D') {
+        (Direction::Debit, after)
+    } else {
+        return Err("invalid debit/credit mark");
+    };
+    // Optional funds code
+    rest = after_mark.trim_start_matches(|c: char| c.is_ascii_uppercase());
+
+    let amount_length = rest
+        .find(|c: char| !c.is_ascii_digit() && c != ',')
+        .unwrap_or(rest.len());
+    let amount = parse_amount(&rest[..amount_length]).ok_or("invalid amount")?;
+    rest = &rest[amount_length..];
+
+    // Transaction type such as NTRF, then the references
+    let references = rest.get(4..).ok_or("missing transaction type")?;
+    let (customer, bank) = match references.split_once("//") {
+        Some((customer, bank)) => (customer, Some(bank.trim())),
+        None => (references, None),
+    };
+    let customer = Some(customer.trim()).filter(|r| !r.is_empty() && *r != NO_REFERENCE);
+
+    Ok(Transaction {
+        id: bank.filter(|b| !b.is_empty()).or(customer).map(Into::into),
+        direction,
+        amount,
+        currency: currency.into(),
+        date,
+        account: Some(account.into()),
+        reference: customer.map(Into::into),
+        ..Transaction::default()
+    })
+}
+
+/// Add the information from a :86: field to a transaction.
+fn read_information(transaction: &mut Transaction, value: &str) {
+    let joined = value.replace('\n', "");
+    let message = if joined.contains('?') {
+        let mut remittance = Vec::new();
+        let mut bank_code = None;
+        for subfield in joined.split('?').skip(1) {
+            let (Some(code), Some(text)) = (subfield.get(..2), subfield.get(2..)) else {
+                continue;
+            };
+            match code.parse::<u8>() {
+                Ok(20..=29 | 60..=63) => remittance.push(text),
+                Ok(30) => bank_code = Some(text),
+                Ok(31) if !text.is_empty() => transaction.counterparty = Some(text.into()),
+                _ => {}
+            }
+        }
+        if let (Some(account), Some(bank)) = (&mut transaction.counterparty, bank_code) {
+            if bank.len() == 4 && bank.bytes().all(|b| b.is_ascii_digit()) && !account.contains('/')
+            {
+                *account = format!("{}/{}", account, bank);
+            }
+        }
+        remittance.join(" ")
+    } else {
+        value.split_whitespace().collect::<Vec<_>>().join(" ")
+    };
+
+    let reference = message
+        .split(' ')
+        .find_map(|word| word.strip_prefix("EREF+"))
+        .filter(|r| !r.is_empty() && *r != "NOTPROVIDED");
+    if let Some(reference) = reference {
+        transaction.reference = Some(reference.into());
+    }
+    transaction.fill_symbols_from(&message);
+    if !message.is_empty() {
+        transaction.message = Some(message);
+    }
+}
+
+impl Mt940Statement {
+    /// Read a statement file from disk.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SpaydError> {
+        std::fs::read_to_string(path)
+            .map_err(|e| SpaydError::Io(e.to_string()))?
+            .parse()
+    }
+
+    /// Parse a file which may contain several statements, each starting with
+    /// a :20: tag.
+    pub fn parse_all(text: &str) -> Result<Vec<Self>, SpaydError> {
+        let mut statements: Vec<Self> = Vec::new();
+        let mut currency = None;
+
+        for field in fields(text)? {
+            let error = |reason: &str| format_error(field.line, reason);
+            if field.tag == "20" {
+                statements.push(Self {
+                    reference: field.value.trim().into(),
+                    account: String::new(),
+                    number: String::new(),
+                    transactions: Vec::new(),
+                });
+                currency = None;
+                continue;
+            }
+
+            let statement = statements.last_mut().ok_or(error("expected :20: first"))?;
+            match field.tag {
+                "25" => statement.account = normalize_account(field.value.trim()),
+                "28C" | "28" => statement.number = field.value.trim().into(),
+                "60F" | "60M" => {
+                    currency = Some(balance_currency(&field.value).ok_or(error("invalid balance"))?)
+                }
+                "61" => {
+                    let currency = currency.as_deref().ok_or(error(":61: before :60F:"))?;
+                    let transaction =
+                        parse_line(&field.value, currency, &statement.account).map_err(&error)?;
+                    statement.transactions.push(transaction);
+                }
+                "86" => {
+                    if let Some(transaction) = statement.transactions.last_mut() {
+                        read_information(transaction, &field.value);
+                    }
+                }
+                "62F" | "62M" => {
+                    balance_currency(&field.value).ok_or(error("invalid balance"))?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(statements)
+    }
+
+    /// The transaction reference from the :20: tag.
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    /// The account from the :25: tag. Czech accounts written with the bank
+    /// code first ("0800/2000145399") are turned around ("2000145399/0800").
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// The statement number from the :28C: tag.
+    pub fn number(&self) -> &str {
+        &self.number
+    }
+
+    /// The transactions in the order they appear.
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// Take the transactions.
+    pub fn into_transactions(self) -> Vec<Transaction> {
+        self.transactions
+    }
+}
+
+impl FromStr for Mt940Statement {
+    type Err = SpaydError;
+
+    /// Parse a single statement. Use `parse_all` for files with several
+    /// statements.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut statements = Self::parse_all(text)?;
+        match statements.len() {
+            0 => Err(format_error(1, "no :20: tag")),
+            1 => Ok(statements.remove(0)),
+            _ => Err(format_error(1, "more than one statement")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Spayd;
+
+    const STATEMENT: &str = "\
+{1:F01KOMBCZPPAXXX0000000000}{2:I940KOMBCZPPXXXXN}{4:
+:20:STMT-2026-200
+:25:5500/1265098001
+:28C:200/1
+:60F:C261015CZK1000,00
+:61:2610161016C500,00NTRFNONREF//TX-1
+:86:/VS/1001/SS/22/KS/0308 Invoice 2026-00123
+:61:2610161017RC250,NTRFORDER-7//TX-2
+:86:166?00SEPA CREDIT?20EREF+E2E-42?21VS 1002?30GIBACZPX?31CZ6508000
+000192000145399?32JAN NOVAK
+:61:261016D12,50NMSCNONREF
+:86:Fee
+:62F:C261016CZK1737,50
+-}";
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let statement: Mt940Statement = STATEMENT.parse().unwrap();
+        assert_eq!(statement.reference(), "STMT-2026-200");
+        assert_eq!(statement.account(), "1265098001/5500");
+        assert_eq!(statement.number(), "200/1");
+
+        let transactions = statement.transactions();
+        assert_eq!(transactions.len(), 3);
+        assert_eq!(
+            transactions[0],
+            Transaction {
+                id: Some("TX-1".into()),
+                direction: Direction::Credit,
+                amount: 50_000,
+                currency: "CZK".into(),
+                date: date(16),
+                account: Some("1265098001/5500".into()),
+                counterparty: None,
+                variable_symbol: Some("1001".into()),
+                specific_symbol: Some("22".into()),
+                constant_symbol: Some("0308".into()),
+                reference: None,
+                message: Some("/VS/1001/SS/22/KS/0308 Invoice 2026-00123".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn structured_information() {
+        let statement: Mt940Statement = STATEMENT.parse().unwrap();
+        let transaction = &statement.transactions()[1];
+
+        assert_eq!(transaction.date, date(17));
+        assert_eq!(transaction.amount, 25_000);
+        assert_eq!(transaction.id.as_deref(), Some("TX-2"));
+        assert_eq!(transaction.reference.as_deref(), Some("E2E-42"));
+        assert_eq!(transaction.variable_symbol.as_deref(), Some("1002"));
+        assert_eq!(
+            transaction.counterparty.as_deref(),
+            Some("CZ6508000000192000145399")
+        );
+        assert_eq!(transaction.message.as_deref(), Some("EREF+E2E-42 VS 1002"));
+    }
+
+    #[test]
+    fn debit_and_reversal() {
+        let statement: Mt940Statement = STATEMENT.parse().unwrap();
+        let transactions = statement.transactions();
+
+        // A reversed credit takes money out of the account
+        assert_eq!(transactions[1].direction, Direction::Debit);
+        assert_eq!(transactions[2].direction, Direction::Debit);
+        assert_eq!(transactions[2].id, None);
+        assert_eq!(transactions[2].amount, 1_250);
+    }
+
+    #[test]
+    fn large_amount() {
+        let text = STATEMENT.replace("C500,00N", "C12500000,00N");
+        let statement: Mt940Statement = text.parse().unwrap();
+        assert_eq!(statement.transactions()[0].amount, 1_250_000_000);
+    }
+
+    #[test]
+    fn satisfies_spayd() {
+        let statement: Mt940Statement = STATEMENT.parse().unwrap();
+        let mut spayd = Spayd::new_v1_0(vec![
+            ("ACC", "CZ5855000000001265098001"),
+            ("AM", "500.00"),
+            ("CC", "CZK"),
+            ("X-VS", "1001"),
+        ]);
+        assert!(statement.transactions()[0].satisfies(&spayd));
+
+        spayd.set_field("CC", "EUR");
+        assert!(!statement.transactions()[0].satisfies(&spayd));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            ":25:1265098001/5500".parse::<Mt940Statement>(),
+            Err(format_error(1, "expected :20: first"))
+        );
+        assert_eq!(
+            STATEMENT
+                .replace("C500,00", "C,,00")
+                .parse::<Mt940Statement>(),
+            Err(format_error(6, "invalid amount"))
+        );
+        assert_eq!(
+            STATEMENT
+                .replace(":60F:", ":60X:")
+                .parse::<Mt940Statement>(),
+            Err(format_error(6, ":61: before :60F:"))
+        );
+        assert_eq!(
+            Mt940Statement::parse_all(&format!("{0}\n{0}", STATEMENT))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
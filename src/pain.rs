@@ -0,0 +1,795 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use iso_currency::Currency;
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::amount::{parse_minor_units, MAX_DECIMALS};
+use crate::reference::{is_digits, MAX_SPAYD_REFERENCE_LENGTH, MAX_VARIABLE_SYMBOL_LENGTH};
+use crate::xml::{currency_attribute, line_at, parse_date, path_ends_with};
+use crate::{currency_decimals, fields, Amount, CreditorReference, IbanBic, Spayd, SpaydError};
+
+const FORMAT: &str = "pain.001 XML";
+
+/// Identifier used for a missing end-to-end ID or debtor agent.
+const NOT_PROVIDED: &str = "NOTPROVIDED";
+
+/// The version of the pain.001 customer credit transfer initiation message.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PainVersion {
+    /// pain.001.001.03, used by SEPA and most Czech banks.
+    V03,
+    /// pain.001.001.09, the version for the SEPA 2019 rulebook onwards.
+    V09,
+}
+
+impl PainVersion {
+    fn namespace(&self) -> &'static str {
+        match self {
+            Self::V03 => "urn:iso:std:iso:20022:tech:xsd:pain.001.001.03",
+            Self::V09 => "urn:iso:std:iso:20022:tech:xsd:pain.001.001.09",
+        }
+    }
+
+    /// The element name for a BIC.
+    fn bic_element(&self) -> &'static str {
+        match self {
+            Self::V03 => "BIC",
+            Self::V09 => "BICFI",
+        }
+    }
+}
+
+/// A batch of payments from one debtor account, converted to and from an
+/// ISO 20022 pain.001 credit transfer initiation.
+///
+/// Each payment's creditor account and BIC come from ACC, the amount from
+/// AM and CC (CZK if missing), the creditor's name from RN and the requested
+/// execution date from DT (or the batch's date). A credit transfer carries
+/// either a structured or an unstructured remittance, so RF is written as a
+/// creditor reference if present, otherwise MSG as free text. The variable
+/// symbol (X-VS) is written as the end-to-end ID.
+/// ```
+/// use chrono::NaiveDate;
+/// use spayd::{IbanBic, PainVersion, PaymentBatch, Spayd};
+///
+/// let date = NaiveDate::from_ymd_opt(2026, 10, 20).unwrap();
+/// let mut batch = PaymentBatch::new("Firma s.r.o.", IbanBic::iban_only("CZ5855000000001265098001"), date);
+/// batch.push(Spayd::new_v1_0(vec![
+///     ("ACC", "CZ6508000000192000145399+GIBACZPX"),
+///     ("AM", "500.00"),
+///     ("X-VS", "1001"),
+/// ]));
+///
+/// let created = date.and_hms_opt(9, 0, 0).unwrap();
+/// let xml = batch.to_pain001(PainVersion::V03, "MSG-1", created).unwrap();
+///
+/// let imported: PaymentBatch = xml.parse().unwrap();
+/// assert_eq!(imported.payments()[0].field("X-VS"), Some("1001"));
+/// assert_eq!(imported.payments()[0].field("DT"), Some("20261020"));
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct PaymentBatch {
+    debtor_name: String,
+    debtor_account: IbanBic,
+    execution_date: NaiveDate,
+    payments: Vec<Spayd>,
+}
+
+/// Builds an indented XML document.
+struct XmlWriter {
+    output: String,
+    depth: usize,
+}
+
+impl XmlWriter {
+    fn new() -> Self {
+        Self {
+            output: "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".into(),
+            depth: 0,
+        }
+    }
+
+    fn start_tag(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.output.push_str(&"  ".repeat(self.depth));
+        self.output.push('<');
+        self.output.push_str(name);
+        for (key, value) in attributes {
+            self.output
+                .push_str(&format!(" {}=\"{}\"", key, escape(*value)));
+        }
+        self.output.push('>');
+    }
+
+    fn open(&mut self, name: &str) {
+        self.open_with(name, &[]);
+    }
+
+    fn open_with(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.start_tag(name, attributes);
+        self.output.push('\n');
+        self.depth += 1;
+    }
+
+    fn close(&mut self, name: &str) {
+        self.depth -= 1;
+        self.output.push_str(&"  ".repeat(self.depth));
+        self.output.push_str(&format!("</{}>\n", name));
+    }
+
+    fn text(&mut self, name: &str, text: &str) {
+        self.text_with(name, &[], text);
+    }
+
+    fn text_with(&mut self, name: &str, attributes: &[(&str, &str)], text: &str) {
+        self.start_tag(name, attributes);
+        self.output
+            .push_str(&format!("{}</{}>\n", escape(text), name));
+    }
+
+    /// A financial institution identified by its BIC, or as not provided.
+    fn agent(&mut self, name: &str, bic: Option<&str>, version: PainVersion) {
+        self.open(name);
+        self.open("FinInstnId");
+        match bic {
+            Some(bic) => self.text(version.bic_element(), bic),
+            None => {
+                self.open("Othr");
+                self.text("Id", NOT_PROVIDED);
+                self.close("Othr");
+            }
+        }
+        self.close("FinInstnId");
+        self.close(name);
+    }
+
+    fn iban_account(&mut self, name: &str, iban: &str) {
+        self.open(name);
+        self.open("Id");
+        self.text("IBAN", iban);
+        self.close("Id");
+        self.close(name);
+    }
+}
+
+/// Format an amount in hundredths with the given number of decimal places,
+/// dropping any minor units beyond them.
+fn format_amount(minor_units: u128, decimals: u32) -> String {
+    let units = minor_units / 10u128.pow(MAX_DECIMALS - decimals);
+    match 10u128.pow(decimals) {
+        1 => units.to_string(),
+        scale => format!(
+            "{}.{:0width$}",
+            units / scale,
+            units % scale,
+            width = decimals as usize
+        ),
+    }
+}
+
+/// A payment with the values needed for a credit transfer.
+struct Transfer<'a> {
+    payment: &'a Spayd,
+    account: IbanBic,
+    amount: Amount,
+    currency: Currency,
+    date: NaiveDate,
+}
+
+impl<'a> Transfer<'a> {
+    fn new(payment: &'a Spayd, default_date: NaiveDate) -> Result<Self, SpaydError> {
+        let date = match payment.field(fields::DUE_DATE) {
+            Some(_) => payment.due_date()?,
+            None => default_date,
+        };
+        let amount = payment.fixed_amount()?;
+        let currency = match payment.field(fields::CURRENCY) {
+            Some(_) => payment.currency()?,
+            None => Currency::CZK,
+        };
+        let decimals = currency_decimals(currency);
+        if amount.minor_units() % 10u64.pow(MAX_DECIMALS - decimals) != 0 {
+            return Err(SpaydError::AmountPrecision {
+                amount: amount.to_string(),
+                currency: currency.code().into(),
+                decimals,
+            });
+        }
+        Ok(Self {
+            payment,
+            account: payment.account()?,
+            amount,
+            currency,
+            date,
+        })
+    }
+
+    fn write(&self, xml: &mut XmlWriter, version: PainVersion) -> Result<(), SpaydError> {
+        let payment = self.payment;
+        xml.open("CdtTrfTxInf");
+        xml.open("PmtId");
+        xml.text(
+            "EndToEndId",
+            payment
+                .field(fields::VARIABLE_SYMBOL)
+                .unwrap_or(NOT_PROVIDED),
+        );
+        xml.close("PmtId");
+        xml.open("Amt");
+        xml.text_with(
+            "InstdAmt",
+            &[("Ccy", self.currency.code())],
+            &format_amount(
+                self.amount.minor_units().into(),
+                currency_decimals(self.currency),
+            ),
+        );
+        xml.close("Amt");
+        if let Some(bic) = &self.account.bic {
+            xml.agent("CdtrAgt", Some(bic), version);
+        }
+        if let Some(name) = payment.field(fields::RECIPIENT) {
+            xml.open("Cdtr");
+            xml.text("Nm", name);
+            xml.close("Cdtr");
+        }
+        xml.iban_account("CdtrAcct", &self.account.iban);
+
+        // Remittance information is either structured or unstructured, so a
+        // creditor reference takes the place of the message.
+        if payment.field(fields::REFERENCE).is_some() {
+            let reference = payment.creditor_reference()?;
+            xml.open("RmtInf");
+            xml.open("Strd");
+            xml.open("CdtrRefInf");
+            xml.open("Tp");
+            xml.open("CdOrPrtry");
+            xml.text("Cd", "SCOR");
+            xml.close("CdOrPrtry");
+            xml.close("Tp");
+            xml.text("Ref", &reference.to_string());
+            xml.close("CdtrRefInf");
+            xml.close("Strd");
+            xml.close("RmtInf");
+        } else if let Some(message) = payment.field(fields::MESSAGE) {
+            xml.open("RmtInf");
+            xml.text("Ustrd", message);
+            xml.close("RmtInf");
+        }
+        xml.close("CdtTrfTxInf");
+        Ok(())
+    }
+}
+
+impl PaymentBatch {
+    /// Create an empty batch paid from the debtor's account, executed on the
+    /// given date unless a payment has its own DT.
+    pub fn new<T>(debtor_name: &str, debtor_account: T, execution_date: NaiveDate) -> Self
+    where
+        T: Into<IbanBic>,
+    {
+        Self {
+            debtor_name: debtor_name.into(),
+            debtor_account: debtor_account.into(),
+            execution_date,
+            payments: Vec::new(),
+        }
+    }
+
+    /// Read a pain.001 file from disk.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SpaydError> {
+        std::fs::read_to_string(path)
+            .map_err(|e| SpaydError::Io(e.to_string()))?
+            .parse()
+    }
+
+    /// Add a payment to the batch.
+    pub fn push(&mut self, payment: Spayd) {
+        self.payments.push(payment);
+    }
+
+    /// The debtor's name.
+    pub fn debtor_name(&self) -> &str {
+        &self.debtor_name
+    }
+
+    /// The account the payments are made from.
+    pub fn debtor_account(&self) -> &IbanBic {
+        &self.debtor_account
+    }
+
+    /// The requested execution date for payments without DT.
+    pub fn execution_date(&self) -> NaiveDate {
+        self.execution_date
+    }
+
+    /// The payments in the batch.
+    pub fn payments(&self) -> &[Spayd] {
+        &self.payments
+    }
+
+    /// Take the payments.
+    pub fn into_payments(self) -> Vec<Spayd> {
+        self.payments
+    }
+
+    /// Write the batch as a pain.001 document with one payment information
+    /// block per execution date. Fails with `FieldMissing` if a payment has
+    /// no ACC or AM, `ConvertError` if ACC, AM, CC, DT or RF is invalid, and
+    /// `AmountPrecision` if AM has more decimal places than CC allows.
+    pub fn to_pain001(
+        &self,
+        version: PainVersion,
+        message_id: &str,
+        created: NaiveDateTime,
+    ) -> Result<String, SpaydError> {
+        let transfers = self
+            .payments
+            .iter()
+            .map(|payment| Transfer::new(payment, self.execution_date))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut dates: Vec<NaiveDate> = transfers.iter().map(|t| t.date).collect();
+        dates.sort();
+        dates.dedup();
+        let total = |transfers: &[&Transfer]| {
+            format_amount(
+                transfers
+                    .iter()
+                    .map(|t| u128::from(t.amount.minor_units()))
+                    .sum(),
+                MAX_DECIMALS,
+            )
+        };
+
+        let mut xml = XmlWriter::new();
+        xml.open_with("Document", &[("xmlns", version.namespace())]);
+        xml.open("CstmrCdtTrfInitn");
+        xml.open("GrpHdr");
+        xml.text("MsgId", message_id);
+        xml.text("CreDtTm", &created.format("%Y-%m-%dT%H:%M:%S").to_string());
+        xml.text("NbOfTxs", &transfers.len().to_string());
+        xml.text("CtrlSum", &total(&transfers.iter().collect::<Vec<_>>()));
+        xml.open("InitgPty");
+        xml.text("Nm", &self.debtor_name);
+        xml.close("InitgPty");
+        xml.close("GrpHdr");
+
+        for (index, date) in dates.iter().enumerate() {
+            let group: Vec<&Transfer> = transfers.iter().filter(|t| t.date == *date).collect();
+            xml.open("PmtInf");
+            xml.text("PmtInfId", &format!("{}-{}", message_id, index + 1));
+            xml.text("PmtMtd", "TRF");
+            xml.text("NbOfTxs", &group.len().to_string());
+            xml.text("CtrlSum", &total(&group));
+            let date = date.format("%Y-%m-%d").to_string();
+            match version {
+                PainVersion::V03 => xml.text("ReqdExctnDt", &date),
+                PainVersion::V09 => {
+                    xml.open("ReqdExctnDt");
+                    xml.text("Dt", &date);
+                    xml.close("ReqdExctnDt");
+                }
+            }
+            xml.open("Dbtr");
+            xml.text("Nm", &self.debtor_name);
+            xml.close("Dbtr");
+            xml.iban_account("DbtrAcct", &self.debtor_account.iban);
+            xml.agent("DbtrAgt", self.debtor_account.bic.as_deref(), version);
+            for transfer in group {
+                transfer.write(&mut xml, version)?;
+            }
+            xml.close("PmtInf");
+        }
+
+        xml.close("CstmrCdtTrfInitn");
+        xml.close("Document");
+        Ok(xml.output)
+    }
+}
+
+/// A credit transfer (`CdtTrfTxInf`) being read.
+struct Payment {
+    spayd: Spayd,
+    currency: Option<String>,
+    iban: Option<String>,
+    bic: Option<String>,
+    messages: Vec<String>,
+}
+
+impl Payment {
+    fn new() -> Self {
+        Self {
+            spayd: Spayd::empty_v1_0(),
+            currency: None,
+            iban: None,
+            bic: None,
+            messages: Vec::new(),
+        }
+    }
+
+    fn finish(mut self, date: Option<NaiveDate>) -> Result<Spayd, &'static str> {
+        let iban = self.iban.ok_or("credit transfer without a creditor IBAN")?;
+        let account = match self.bic {
+            Some(bic) => IbanBic::iban_bic(iban, bic),
+            None => IbanBic::iban_only(iban),
+        };
+        self.spayd.set_account(account);
+        if self.spayd.field(fields::AMOUNT).is_none() {
+            return Err("credit transfer without an amount");
+        }
+        if let Some(currency) = self.currency {
+            self.spayd.set_field(fields::CURRENCY, currency);
+            // Write the amount with the currency's decimal places, e.g. "1500"
+            // for JPY. An unknown currency or an amount too precise for it
+            // is left for validation to report.
+            if let Ok(amount) = self.spayd.amount() {
+                self.spayd.set_amount_exact(&amount).ok();
+            }
+        }
+        if let Some(date) = date {
+            self.spayd.set_due_date(&date);
+        }
+        if !self.messages.is_empty() {
+            self.spayd
+                .set_field(fields::MESSAGE, self.messages.join(" "));
+        }
+        Ok(self.spayd)
+    }
+
+    /// Store a creditor reference in RF if it's numeric, or in the message.
+    fn add_reference(&mut self, text: &str) {
+        let spayd_reference = match text.parse::<CreditorReference>() {
+            Ok(reference) => reference.spayd_reference().map(str::to_owned),
+            Err(_) => Some(text.to_owned()).filter(|t| is_digits(t, MAX_SPAYD_REFERENCE_LENGTH)),
+        };
+        match spayd_reference {
+            Some(reference) => self.spayd.set_field(fields::REFERENCE, reference),
+            None => self.messages.push(text.into()),
+        }
+    }
+}
+
+/// The parts of a batch read so far.
+#[derive(Default)]
+struct Reading {
+    debtor_name: Option<String>,
+    debtor_iban: Option<String>,
+    debtor_bic: Option<String>,
+    execution_date: Option<NaiveDate>,
+    first_date: Option<NaiveDate>,
+    payment: Option<Payment>,
+    payments: Vec<Spayd>,
+}
+
+impl Reading {
+    /// Store the text of an element according to where it is in the document.
+    fn read_text(&mut self, path: &[String], text: &str) -> Result<(), String> {
+        let is_bic = |path: &[String]| {
+            path_ends_with(path, &["FinInstnId", "BIC"])
+                || path_ends_with(path, &["FinInstnId", "BICFI"])
+        };
+
+        let Some(payment) = &mut self.payment else {
+            if path_ends_with(path, &["PmtInf", "Dbtr", "Nm"]) {
+                self.debtor_name.get_or_insert_with(|| text.into());
+            } else if path_ends_with(path, &["DbtrAcct", "Id", "IBAN"]) {
+                self.debtor_iban.get_or_insert_with(|| text.into());
+            } else if path.iter().any(|n| n == "DbtrAgt") && is_bic(path) {
+                self.debtor_bic.get_or_insert_with(|| text.into());
+            } else if path_ends_with(path, &["PmtInf", "ReqdExctnDt"])
+                || path_ends_with(path, &["ReqdExctnDt", "Dt"])
+                || path_ends_with(path, &["ReqdExctnDt", "DtTm"])
+            {
+                let date = parse_date(text).ok_or("invalid execution date")?;
+                self.execution_date = Some(date);
+                self.first_date.get_or_insert(date);
+            }
+            return Ok(());
+        };
+
+        if path_ends_with(path, &["PmtId", "EndToEndId"]) {
+            if is_digits(text, MAX_VARIABLE_SYMBOL_LENGTH) {
+                payment
+                    .spayd
+                    .set_field(fields::VARIABLE_SYMBOL, text.to_owned());
+            }
+        } else if path_ends_with(path, &["Amt", "InstdAmt"]) {
+            let amount = parse_minor_units(text)
+                .and_then(Amount::from_minor_units)
+                .ok_or_else(|| format!("invalid amount '{}'", text))?;
+            payment.spayd.set_fixed_amount(amount);
+        } else if path.iter().any(|n| n == "CdtrAgt") && is_bic(path) {
+            payment.bic = Some(text.into());
+        } else if path_ends_with(path, &["Cdtr", "Nm"]) {
+            payment.spayd.set_field(fields::RECIPIENT, text.to_owned());
+        } else if path_ends_with(path, &["CdtrAcct", "Id", "IBAN"]) {
+            payment.iban = Some(text.into());
+        } else if path_ends_with(path, &["RmtInf", "Ustrd"]) {
+            payment.messages.push(text.into());
+        } else if path_ends_with(path, &["CdtrRefInf", "Ref"]) {
+            payment.add_reference(text);
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PaymentBatch {
+    type Err = SpaydError;
+
+    /// Parse a pain.001.001.03 or pain.001.001.09 document. All credit
+    /// transfers are read into one batch, each with DT set to the requested
+    /// execution date of its payment information block.
+    fn from_str(xml: &str) -> Result<Self, Self::Err> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+
+        let mut found = false;
+        let mut path: Vec<String> = Vec::new();
+        let mut reading = Reading::default();
+
+        loop {
+            let position = reader.buffer_position() as usize;
+            let error = |reason: &str| SpaydError::FileFormat {
+                format: FORMAT,
+                line: line_at(xml, position),
+                reason: reason.into(),
+            };
+
+            let event = reader.read_event().map_err(|e| error(&e.to_string()))?;
+            match event {
+                Event::Start(start) => {
+                    let name = String::from_utf8_lossy(start.local_name().as_ref()).into_owned();
+                    match name.as_str() {
+                        "CstmrCdtTrfInitn" => found = true,
+                        "PmtInf" => reading.execution_date = None,
+                        "CdtTrfTxInf" => reading.payment = Some(Payment::new()),
+                        "InstdAmt" => {
+                            if let Some(payment) = &mut reading.payment {
+                                payment.currency = currency_attribute(&start);
+                            }
+                        }
+                        _ => {}
+                    }
+                    path.push(name);
+                }
+                Event::End(_) => {
+                    if path.last().map(String::as_str) == Some("CdtTrfTxInf") {
+                        if let Some(payment) = reading.payment.take() {
+                            let spayd = payment.finish(reading.execution_date).map_err(error)?;
+                            reading.payments.push(spayd);
+                        }
+                    }
+                    path.pop();
+                }
+                Event::Text(text) => {
+                    let text = text.unescape().map_err(|e| error(&e.to_string()))?;
+                    reading
+                        .read_text(&path, text.trim())
+                        .map_err(|reason| error(&reason))?;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        let error = |reason: &str| SpaydError::FileFormat {
+            format: FORMAT,
+            line: 1,
+            reason: reason.into(),
+        };
+        if !found {
+            return Err(error("not a pain.001 document"));
+        }
+        let iban = reading.debtor_iban.ok_or_else(|| error("no debtor IBAN"))?;
+        let execution_date = reading
+            .first_date
+            .ok_or_else(|| error("no requested execution date"))?;
+        Ok(Self {
+            debtor_name: reading.debtor_name.unwrap_or_default(),
+            debtor_account: match reading.debtor_bic {
+                Some(bic) => IbanBic::iban_bic(iban, bic),
+                None => IbanBic::iban_only(iban),
+            },
+            execution_date,
+            payments: reading.payments,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn batch() -> PaymentBatch {
+        let mut batch = PaymentBatch::new(
+            "Firma & syn s.r.o.",
+            IbanBic::iban_bic("CZ5855000000001265098001", "RZBCCZPP"),
+            date(20),
+        );
+        batch.push(Spayd::new_v1_0(vec![
+            ("ACC", "CZ6508000000192000145399+GIBACZPX"),
+            ("AM", "500.00"),
+            ("CC", "CZK"),
+            ("RN", "Dodavatel a.s."),
+            ("X-VS", "1001"),
+            ("RF", "539007547034"),
+        ]));
+        batch.push(Spayd::new_v1_0(vec![
+            ("ACC", "DE89370400440532013000"),
+            ("AM", "12.5"),
+            ("CC", "EUR"),
+            ("DT", "20261022"),
+            ("MSG", "Faktura 2026-00123"),
+        ]));
+        batch
+    }
+
+    fn created() -> NaiveDateTime {
+        date(19).and_hms_opt(9, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn export_v03() {
+        let xml = batch()
+            .to_pain001(PainVersion::V03, "MSG-1", created())
+            .unwrap();
+        assert!(xml.contains("xmlns=\"urn:iso:std:iso:20022:tech:xsd:pain.001.001.03\""));
+        assert!(xml.contains("<CreDtTm>2026-10-19T09:30:00</CreDtTm>"));
+        assert!(xml.contains("<NbOfTxs>2</NbOfTxs>"));
+        assert!(xml.contains("<CtrlSum>512.50</CtrlSum>"));
+        assert!(xml.contains("<Nm>Firma &amp; syn s.r.o.</Nm>"));
+        assert!(xml.contains("<ReqdExctnDt>2026-10-20</ReqdExctnDt>"));
+        assert!(xml.contains("<ReqdExctnDt>2026-10-22</ReqdExctnDt>"));
+        assert!(xml.contains("<PmtInfId>MSG-1-2</PmtInfId>"));
+        assert!(xml.contains("<BIC>RZBCCZPP</BIC>"));
+        assert!(xml.contains("<BIC>GIBACZPX</BIC>"));
+        assert!(xml.contains("<EndToEndId>1001</EndToEndId>"));
+        assert!(xml.contains("<EndToEndId>NOTPROVIDED</EndToEndId>"));
+        assert!(xml.contains("<InstdAmt Ccy=\"CZK\">500.00</InstdAmt>"));
+        assert!(xml.contains("<InstdAmt Ccy=\"EUR\">12.50</InstdAmt>"));
+        assert!(xml.contains("<Nm>Dodavatel a.s.</Nm>"));
+        assert_eq!(xml.matches("<Cdtr>").count(), 1);
+        assert!(xml.contains("<Ref>RF18539007547034</Ref>"));
+        assert!(xml.contains("<Ustrd>Faktura 2026-00123</Ustrd>"));
+        assert_eq!(xml.matches("<RmtInf>").count(), 2);
+        assert_eq!(xml.matches("<Strd>").count(), 1);
+    }
+
+    #[test]
+    fn export_v09() {
+        let mut batch = batch();
+        batch.debtor_account = IbanBic::iban_only("CZ5855000000001265098001");
+        let xml = batch
+            .to_pain001(PainVersion::V09, "MSG-1", created())
+            .unwrap();
+        assert!(xml.contains("xmlns=\"urn:iso:std:iso:20022:tech:xsd:pain.001.001.09\""));
+        assert!(xml.contains("<ReqdExctnDt>\n        <Dt>2026-10-20</Dt>"));
+        assert!(xml.contains("<BICFI>GIBACZPX</BICFI>"));
+        assert!(xml.contains("<Othr>\n            <Id>NOTPROVIDED</Id>"));
+        assert!(!xml.contains("<BIC>"));
+    }
+
+    #[test]
+    fn export_errors() {
+        let mut batch = batch();
+        batch.push(Spayd::new_v1_0(vec![("ACC", "CZ5855000000001265098001")]));
+        assert_eq!(
+            batch.to_pain001(PainVersion::V03, "MSG-1", created()),
+            Err(SpaydError::FieldMissing("AM".into()))
+        );
+
+        let mut batch = PaymentBatch::new("Firma", IbanBic::iban_only("CZ58"), date(20));
+        batch.push(Spayd::new_v1_0(vec![
+            ("ACC", "CZ5855000000001265098001"),
+            ("AM", "1"),
+            ("RF", "INV-1"),
+        ]));
+        assert_eq!(
+            batch.to_pain001(PainVersion::V03, "MSG-1", created()),
+            Err(SpaydError::ConvertError("INV-1".into()))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for version in [PainVersion::V03, PainVersion::V09] {
+            let xml = batch().to_pain001(version, "MSG-1", created()).unwrap();
+            let imported: PaymentBatch = xml.parse().unwrap();
+
+            let mut expected = batch();
+            expected.payments[0].set_field("DT", "20261020");
+            expected.payments[1].set_field("AM", "12.50");
+            assert_eq!(imported, expected, "{version:?}");
+        }
+    }
+
+    #[test]
+    fn import_references() {
+        let xml = batch()
+            .to_pain001(PainVersion::V03, "MSG-1", created())
+            .unwrap()
+            .replace(
+                "<EndToEndId>1001</EndToEndId>",
+                "<EndToEndId>INV-1001</EndToEndId>",
+            )
+            .replace("RF18539007547034", "ABC/123");
+        let imported: PaymentBatch = xml.parse().unwrap();
+        let payment = &imported.payments()[0];
+        assert_eq!(payment.field("X-VS"), None);
+        assert_eq!(payment.field("RF"), None);
+        assert_eq!(payment.field("MSG"), Some("ABC/123"));
+    }
+
+    #[test]
+    fn export_currency_decimals() {
+        let mut batch = PaymentBatch::new(
+            "Firma",
+            IbanBic::iban_only("CZ5855000000001265098001"),
+            date(20),
+        );
+        batch.push(Spayd::new_v1_0(vec![
+            ("ACC", "CZ6508000000192000145399"),
+            ("AM", "1500"),
+            ("CC", "JPY"),
+        ]));
+        let xml = batch
+            .to_pain001(PainVersion::V03, "MSG-1", created())
+            .unwrap();
+        assert!(xml.contains("<InstdAmt Ccy=\"JPY\">1500</InstdAmt>"));
+        assert!(xml.contains("<CtrlSum>1500.00</CtrlSum>"));
+
+        let imported: PaymentBatch = xml.parse().unwrap();
+        assert_eq!(imported.payments()[0].field("AM"), Some("1500"));
+
+        batch.payments[0].set_field("AM", "1500.50");
+        assert!(matches!(
+            batch.to_pain001(PainVersion::V03, "MSG-1", created()),
+            Err(SpaydError::AmountPrecision { decimals: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn import_large_amount() {
+        let xml = batch()
+            .to_pain001(PainVersion::V03, "MSG-1", created())
+            .unwrap()
+            .replace(">500.00<", ">10000000.00<");
+        let imported: PaymentBatch = xml.parse().unwrap();
+        assert_eq!(imported.payments()[0].field("AM"), Some("10000000"));
+    }
+
+    #[test]
+    fn import_errors() {
+        let error = |line, reason: &str| {
+            Err(SpaydError::FileFormat {
+                format: FORMAT,
+                line,
+                reason: reason.into(),
+            })
+        };
+        assert_eq!(
+            "<Document><BkToCstmrStmt/></Document>".parse::<PaymentBatch>(),
+            error(1, "not a pain.001 document")
+        );
+
+        let xml = batch()
+            .to_pain001(PainVersion::V03, "MSG-1", created())
+            .unwrap();
+        assert_eq!(
+            xml.replace(">500.00<", ">5,00<").parse::<PaymentBatch>(),
+            error(37, "invalid amount '5,00'")
+        );
+        assert_eq!(
+            xml.replace("<IBAN>DE89370400440532013000</IBAN>", "")
+                .parse::<PaymentBatch>(),
+            error(99, "credit transfer without a creditor IBAN")
+        );
+    }
+}
@@ -1,18 +1,46 @@
 use std::str::FromStr;
 
 use crate::error::SpaydError;
-use crate::spayd::{Spayd, SpaydVersion};
+use crate::spayd::{check_key, Spayd, SpaydVersion};
 use nom::{
-    bytes::complete::{is_not, tag, take_until1, take_while},
+    bytes::complete::{is_not, tag, take_while},
     character::complete::digit1,
     combinator::{all_consuming, map, map_parser, map_res},
-    error::{Error, ErrorKind},
+    error::{Error, ErrorKind, ParseError},
     multi::separated_list1,
     sequence::{delimited, pair, separated_pair},
-    Finish, IResult,
+    Err as NomErr, Finish, IResult,
 };
 use percent_encoding::percent_decode_str;
 
+/// Errors from the field parsers. Syntax errors allow the parser to
+/// backtrack, field errors are found once a field's structure has been
+/// parsed and stop parsing immediately.
+#[derive(Debug, PartialEq)]
+enum FieldParseError<'a> {
+    Syntax(Error<&'a str>),
+    Field(SpaydError),
+}
+
+impl<'a> ParseError<&'a str> for FieldParseError<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        Self::Syntax(Error::new(input, kind))
+    }
+
+    fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl From<FieldParseError<'_>> for SpaydError {
+    fn from(value: FieldParseError<'_>) -> Self {
+        match value {
+            FieldParseError::Syntax(e) => e.into(),
+            FieldParseError::Field(e) => e,
+        }
+    }
+}
+
 fn version_section(input: &str) -> IResult<&str, u32> {
     map_res(digit1, str::parse)(input)
 }
@@ -28,32 +56,44 @@ fn header(input: &str) -> IResult<&str, SpaydVersion> {
     delimited(tag("SPD*"), version, tag("*"))(input)
 }
 
-fn decode_percent_encoding(i: &str) -> Result<String, Error<&str>> {
-    match percent_decode_str(i).decode_utf8() {
-        Ok(t) => Ok(t.to_string()),
-        Err(_) => Err(Error::new(i, ErrorKind::Escaped)),
+/// Decode a percent encoded value. Unlike `percent_decode_str`, a `%` which
+/// isn't followed by two hex digits is an error rather than a literal `%`.
+fn decode_percent_encoding(text: &str) -> Result<String, SpaydError> {
+    let bytes = text.as_bytes();
+    for (i, _) in text.match_indices('%') {
+        match bytes.get(i + 1..i + 3) {
+            Some([a, b]) if a.is_ascii_hexdigit() && b.is_ascii_hexdigit() => {}
+            _ => return Err(SpaydError::MalformedEscape(text.into())),
+        }
+    }
+
+    match percent_decode_str(text).decode_utf8() {
+        Ok(t) => Ok(t.into_owned()),
+        Err(_) => Err(SpaydError::InvalidUtf8(text.into())),
     }
 }
 
-fn decode_spayd_kv<'a>(k: &'a str, v: &'a str) -> Result<(String, String), Error<&'a str>> {
-    let k = decode_percent_encoding(k)?;
+fn decode_spayd_kv(k: &str, v: &str) -> Result<(String, String), SpaydError> {
+    check_key(k)?;
     let v = decode_percent_encoding(v)?;
-    Ok((k, v))
+    Ok((k.to_owned(), v))
 }
 
-fn kv_pair(input: &str) -> IResult<&str, (String, String)> {
-    map_res(
-        separated_pair(take_until1(":"), tag(":"), is_not("*")),
-        |(k, v)| decode_spayd_kv(k, v),
-    )(input)
+fn kv_pair(input: &str) -> IResult<&str, (String, String), FieldParseError<'_>> {
+    let (rest, (k, v)) = separated_pair(is_not(":*"), tag(":"), is_not("*"))(input)?;
+    match decode_spayd_kv(k, v) {
+        Ok(kv) => Ok((rest, kv)),
+        Err(e) => Err(NomErr::Failure(FieldParseError::Field(e))),
+    }
 }
 
-fn values(input: &str) -> IResult<&str, Vec<(String, String)>> {
+fn values(input: &str) -> IResult<&str, Vec<(String, String)>, FieldParseError<'_>> {
     separated_list1(tag("*"), kv_pair)(input)
 }
 
-fn full_text(input: &str) -> IResult<&str, Spayd> {
-    map(pair(header, values), |(version, values)| {
+fn full_text(input: &str) -> IResult<&str, Spayd, FieldParseError<'_>> {
+    let syntax_header = |i| header(i).map_err(|e| e.map(FieldParseError::Syntax));
+    map(pair(syntax_header, values), |(version, values)| {
         Spayd::new(version, values)
     })(input)
 }
@@ -63,15 +103,30 @@ fn is_ascii_printable(c: char) -> bool {
 }
 
 /// Parse text into a Spayd value.
+///
+/// Keys must consist of uppercase letters, digits and `-`. Values must not
+/// be empty and are percent decoded, with escapes in either hex case being
+/// accepted. As
+/// formatting a SPAYD always produces uppercase escapes and escapes every
+/// character the parser treats specially, parsing the formatted text of a
+/// parsed SPAYD always gives the same SPAYD again.
 fn parse_spayd(input: &str) -> Result<Spayd, SpaydError> {
-    let parsed =
-        all_consuming(map_parser(take_while(is_ascii_printable), full_text))(input).finish()?;
-
-    let spayd = parsed.1;
+    let spayd = parse_unvalidated(input)?;
     spayd.validate()?;
     Ok(spayd)
 }
 
+/// Parse text into a Spayd value without validating it, e.g. to repair a
+/// SPAYD whose CRC32 check fails.
+pub(crate) fn parse_unvalidated(input: &str) -> Result<Spayd, SpaydError> {
+    let parsed = all_consuming(map_parser(
+        take_while(is_ascii_printable),
+        all_consuming(full_text),
+    ))(input)
+    .finish()?;
+    Ok(parsed.1)
+}
+
 impl FromStr for Spayd {
     type Err = SpaydError;
 
@@ -189,8 +244,82 @@ mod tests {
         assert!("SPD*1.0*ACC:".parse::<Spayd>().is_err());
     }
 
+    #[test]
+    fn empty_value() {
+        let input = "SPD*1.0*ACC:CZ5855000000001265098001*MSG:";
+        assert!(input.parse::<Spayd>().is_err());
+        assert!(parse_unvalidated(input).is_err());
+        assert!("SPD*1.0*ACC:CZ5855000000001265098001*MSG:*X-VS:1"
+            .parse::<Spayd>()
+            .is_err());
+    }
+
     #[test]
     fn non_ascii() {
         assert!("SPD*1.0*PŘÍKLAD:123".parse::<Spayd>().is_err());
     }
+
+    #[test]
+    fn invalid_keys() {
+        assert_eq!(
+            "SPD*1.0*ACC:CZ5855000000001265098001*msg:hello".parse::<Spayd>(),
+            Err(SpaydError::InvalidKey("msg".into()))
+        );
+        assert_eq!(
+            "SPD*1.0*ACC:CZ5855000000001265098001*M%53G:hello".parse::<Spayd>(),
+            Err(SpaydError::InvalidKey("M%53G".into()))
+        );
+        assert_eq!(
+            "SPD*1.0*ACC:CZ5855000000001265098001*X-VS:1*X_ID:2".parse::<Spayd>(),
+            Err(SpaydError::InvalidKey("X_ID".into()))
+        );
+    }
+
+    #[test]
+    fn malformed_escapes() {
+        for value in ["%", "100%", "%4", "%G0", "%%41"] {
+            assert_eq!(
+                format!("SPD*1.0*ACC:CZ5855000000001265098001*MSG:{value}").parse::<Spayd>(),
+                Err(SpaydError::MalformedEscape(value.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_utf8_escape() {
+        assert_eq!(
+            "SPD*1.0*ACC:CZ5855000000001265098001*MSG:%C5".parse::<Spayd>(),
+            Err(SpaydError::InvalidUtf8("%C5".into()))
+        );
+    }
+
+    #[test]
+    fn escape_case_normalized() {
+        let spayd: Spayd = "SPD*1.0*ACC:CZ5855000000001265098001*MSG:%2a%c5%98"
+            .parse()
+            .unwrap();
+        assert_eq!(spayd.field("MSG"), Some("*Ř"));
+        assert_eq!(
+            spayd.to_string(),
+            "SPD*1.0*ACC:CZ5855000000001265098001*MSG:%2A%C5%98"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let inputs = [
+            "SPD*1.0*ACC:CZ5855000000001265098001*AM:480.50*CC:CZK*MSG:Payment for the goods",
+            "SPD*1.0*ACC:CZ5855000000001265098001+RZBCCZPP*RN:Krte%c4%8dek*X-VS:1234",
+            "SPD*1.0*ACC:CZ5855000000001265098001*MSG:%40%3F%2A%24%21%25 :+-./",
+            "SPD*1.0*ACC:CZ5855000000001265098001*MSG:a:b:c*X-URL:http%3A//example.com/?a=1&b=2",
+            "SPD*1.0*ACC:CZ5855000000001265098001*MSG:~`'\"[]{}|\\^<>,;=#",
+            "SPD*1.2*ALT-ACC:CZ5855000000001265098001,CZ5855000000001265098001*ACC:X",
+        ];
+        for input in inputs {
+            let parsed: Spayd = input.parse().unwrap();
+            let reparsed: Spayd = parsed.to_string().parse().unwrap();
+            assert_eq!(reparsed, parsed);
+            assert_eq!(reparsed.to_string(), parsed.to_string());
+        }
+    }
 }
@@ -0,0 +1,411 @@
+//! Python bindings built with PyO3.
+//!
+//! The extension module is named `spayd` and provides the `Spayd` and
+//! `IbanBic` classes. Errors are raised as subclasses of `spayd.SpaydError`
+//! named after the Rust error variants. Amounts are exposed as
+//! `decimal.Decimal` and due dates as `datetime.date`.
+//!
+//! The extension module is built by maturin (see `pyproject.toml`), which
+//! builds the crate as a `cdylib` itself.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyIOError};
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::{IbanBic, Spayd, SpaydError, SpaydVersion};
+
+create_exception!(
+    spayd,
+    PySpaydError,
+    PyException,
+    "Base class for SPAYD errors."
+);
+create_exception!(
+    spayd,
+    ParseError,
+    PySpaydError,
+    "The text couldn't be parsed."
+);
+create_exception!(
+    spayd,
+    RequiredFieldMissing,
+    PySpaydError,
+    "A field required by the SPAYD standard is missing."
+);
+create_exception!(
+    spayd,
+    Crc32Failed,
+    PySpaydError,
+    "The data doesn't match the CRC32 checksum."
+);
+create_exception!(
+    spayd,
+    ConvertError,
+    PySpaydError,
+    "A field has an incorrect format."
+);
+create_exception!(spayd, FieldMissing, PySpaydError, "The field is missing.");
+create_exception!(
+    spayd,
+    InvalidKey,
+    PySpaydError,
+    "A field name isn't allowed by the standard."
+);
+create_exception!(
+    spayd,
+    InvalidValue,
+    PySpaydError,
+    "A field value contains characters which aren't allowed."
+);
+create_exception!(
+    spayd,
+    MalformedEscape,
+    PySpaydError,
+    "A field value contains a malformed percent escape."
+);
+create_exception!(
+    spayd,
+    InvalidUtf8,
+    PySpaydError,
+    "A field value's percent escapes aren't valid UTF-8."
+);
+create_exception!(
+    spayd,
+    AmountPrecision,
+    PySpaydError,
+    "The amount has more decimal places than its currency allows."
+);
+create_exception!(
+    spayd,
+    FileFormat,
+    PySpaydError,
+    "A file or document isn't in the expected format."
+);
+create_exception!(
+    spayd,
+    ExportError,
+    PySpaydError,
+    "A payment can't be exported to a file format."
+);
+create_exception!(
+    spayd,
+    UnsupportedVersion,
+    PySpaydError,
+    "The version of the SPAYD isn't supported."
+);
+
+impl From<SpaydError> for PyErr {
+    fn from(error: SpaydError) -> Self {
+        let message = error.to_string();
+        match error {
+            SpaydError::ParseError(_) => ParseError::new_err(message),
+            SpaydError::RequiredFieldMissing(_) => RequiredFieldMissing::new_err(message),
+            #[cfg(feature = "crc32")]
+            SpaydError::Crc32Failed => Crc32Failed::new_err(message),
+            SpaydError::ConvertError(_) => ConvertError::new_err(message),
+            SpaydError::FieldMissing(_) => FieldMissing::new_err(message),
+            SpaydError::UnsupportedVersion(_) => UnsupportedVersion::new_err(message),
+            SpaydError::InvalidKey(_) => InvalidKey::new_err(message),
+            SpaydError::InvalidValue(_) => InvalidValue::new_err(message),
+            SpaydError::MalformedEscape(_) => MalformedEscape::new_err(message),
+            SpaydError::InvalidUtf8(_) => InvalidUtf8::new_err(message),
+            SpaydError::AmountPrecision { .. } => AmountPrecision::new_err(message),
+            SpaydError::Io(_) => PyIOError::new_err(message),
+            SpaydError::FileFormat { .. } => FileFormat::new_err(message),
+            SpaydError::ExportError { .. } => ExportError::new_err(message),
+        }
+    }
+}
+
+/// Separated IBAN and BIC of an account.
+#[pyclass(name = "IbanBic", module = "spayd", eq, from_py_object)]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PyIbanBic {
+    /// International Bank Account Number
+    #[pyo3(get, set)]
+    pub iban: String,
+    /// Bank Identifier Code (ISO 9362)
+    #[pyo3(get, set)]
+    pub bic: Option<String>,
+}
+
+#[pymethods]
+impl PyIbanBic {
+    #[new]
+    #[pyo3(signature = (iban, bic=None))]
+    fn new(iban: &str, bic: Option<&str>) -> Self {
+        match bic {
+            Some(bic) => IbanBic::iban_bic(iban, bic),
+            None => IbanBic::iban_only(iban),
+        }
+        .into()
+    }
+
+    fn __str__(&self) -> String {
+        IbanBic::from(self.clone()).to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.bic {
+            Some(bic) => format!("IbanBic({:?}, {:?})", self.iban, bic),
+            None => format!("IbanBic({:?})", self.iban),
+        }
+    }
+}
+
+impl From<IbanBic> for PyIbanBic {
+    fn from(value: IbanBic) -> Self {
+        Self {
+            iban: value.iban,
+            bic: value.bic,
+        }
+    }
+}
+
+impl From<PyIbanBic> for IbanBic {
+    fn from(value: PyIbanBic) -> Self {
+        Self {
+            iban: value.iban,
+            bic: value.bic,
+        }
+    }
+}
+
+/// A Short Payment Descriptor.
+#[pyclass(name = "Spayd", module = "spayd", eq, from_py_object)]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PySpayd {
+    inner: Spayd,
+}
+
+#[pymethods]
+impl PySpayd {
+    /// Create a version 1.0 SPAYD, optionally from a dict of fields.
+    #[new]
+    #[pyo3(signature = (fields=None))]
+    fn new(fields: Option<BTreeMap<String, String>>) -> Self {
+        Self {
+            inner: Spayd::new_v1_0(fields.unwrap_or_default()),
+        }
+    }
+
+    /// Parse and validate SPAYD text.
+    #[staticmethod]
+    fn parse(text: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: text.parse()?,
+        })
+    }
+
+    /// Create a SPAYD from a dict of fields, with an optional version
+    /// given as a `(major, minor)` tuple.
+    #[staticmethod]
+    #[pyo3(signature = (fields, version=(1, 0)))]
+    fn from_dict(fields: BTreeMap<String, String>, version: (u32, u32)) -> Self {
+        Self {
+            inner: Spayd::new(SpaydVersion::new(version.0, version.1), fields),
+        }
+    }
+
+    /// The fields as a dict.
+    fn to_dict(&self) -> BTreeMap<String, String> {
+        self.inner
+            .iter()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect()
+    }
+
+    /// The version number as a `(major, minor)` tuple.
+    #[getter]
+    fn version(&self) -> (u32, u32) {
+        let version = self.inner.version();
+        (version.major, version.minor)
+    }
+
+    /// Ensure that the SPAYD has all required fields and that the CRC check
+    /// is correct.
+    fn validate(&self) -> PyResult<()> {
+        Ok(self.inner.validate()?)
+    }
+
+    /// Get the value of the given field.
+    fn field(&self, key: &str) -> Option<&str> {
+        self.inner.field(key)
+    }
+
+    /// Set the value of the given field.
+    fn set_field(&mut self, key: &str, value: &str) -> PyResult<()> {
+        Ok(self.inner.try_set_field(key, value)?)
+    }
+
+    /// The main account.
+    #[getter]
+    fn account(&self) -> PyResult<PyIbanBic> {
+        Ok(self.inner.account()?.into())
+    }
+
+    #[setter]
+    fn set_account(&mut self, account: PyIbanBic) {
+        self.inner.set_account(IbanBic::from(account));
+    }
+
+    /// Alternative accounts.
+    #[getter]
+    fn alternative_accounts(&self) -> PyResult<Vec<PyIbanBic>> {
+        Ok(self
+            .inner
+            .alternative_accounts()?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    #[setter]
+    fn set_alternative_accounts(&mut self, accounts: Vec<PyIbanBic>) {
+        self.inner
+            .set_alternative_accounts(accounts.into_iter().map(IbanBic::from));
+    }
+
+    /// The payment amount as a `decimal.Decimal`.
+    #[getter]
+    fn amount(&self) -> PyResult<Decimal> {
+        Ok(self.inner.amount()?)
+    }
+
+    #[setter]
+    fn set_amount(&mut self, amount: Decimal) {
+        self.inner.set_amount(&amount);
+    }
+
+    /// The due date as a `datetime.date`.
+    #[getter]
+    fn due_date(&self) -> PyResult<NaiveDate> {
+        Ok(self.inner.due_date()?)
+    }
+
+    #[setter]
+    fn set_due_date(&mut self, date: NaiveDate) {
+        self.inner.set_due_date(&date);
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Spayd.parse({:?})", self.inner.to_string())
+    }
+}
+
+#[pymodule(name = "spayd")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add_class::<PySpayd>()?;
+    m.add_class::<PyIbanBic>()?;
+    m.add("SpaydError", py.get_type::<PySpaydError>())?;
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add(
+        "RequiredFieldMissing",
+        py.get_type::<RequiredFieldMissing>(),
+    )?;
+    m.add("Crc32Failed", py.get_type::<Crc32Failed>())?;
+    m.add("ConvertError", py.get_type::<ConvertError>())?;
+    m.add("FieldMissing", py.get_type::<FieldMissing>())?;
+    m.add("UnsupportedVersion", py.get_type::<UnsupportedVersion>())?;
+    m.add("InvalidKey", py.get_type::<InvalidKey>())?;
+    m.add("InvalidValue", py.get_type::<InvalidValue>())?;
+    m.add("MalformedEscape", py.get_type::<MalformedEscape>())?;
+    m.add("InvalidUtf8", py.get_type::<InvalidUtf8>())?;
+    m.add("AmountPrecision", py.get_type::<AmountPrecision>())?;
+    m.add("FileFormat", py.get_type::<FileFormat>())?;
+    m.add("ExportError", py.get_type::<ExportError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+    use std::ffi::CStr;
+
+    fn run(code: &CStr) {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "spayd").unwrap();
+            python_module(&module).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("spayd", module).unwrap();
+            if let Err(e) = py.run(code, Some(&globals), None) {
+                panic!("{}", e);
+            }
+        })
+    }
+
+    #[test]
+    fn parse_and_dict() {
+        run(c"
+s = spayd.Spayd.parse('SPD*1.0*ACC:CZ5855000000001265098001*AM:480.50*CC:CZK')
+assert s.version == (1, 0)
+assert s.to_dict() == {'ACC': 'CZ5855000000001265098001', 'AM': '480.50', 'CC': 'CZK'}
+assert s.field('MSG') is None
+assert str(s) == 'SPD*1.0*ACC:CZ5855000000001265098001*AM:480.50*CC:CZK'
+assert spayd.Spayd.from_dict(s.to_dict()) == s
+");
+    }
+
+    #[test]
+    fn exceptions() {
+        run(c"
+try:
+    spayd.Spayd.parse('SPD*1.0*AM:480.50')
+    assert False
+except spayd.RequiredFieldMissing as e:
+    assert isinstance(e, spayd.SpaydError)
+    assert str(e) == \"the required field 'ACC' is missing\"
+
+try:
+    spayd.Spayd.parse('SPD*1.0*ACC')
+    assert False
+except spayd.ParseError:
+    pass
+
+try:
+    spayd.Spayd().set_field('msg', 'hello')
+    assert False
+except spayd.InvalidKey:
+    pass
+
+try:
+    spayd.Spayd().amount
+    assert False
+except spayd.FieldMissing:
+    pass
+");
+    }
+
+    #[test]
+    fn typed_accessors() {
+        run(c"
+import datetime
+import decimal
+
+s = spayd.Spayd()
+s.account = spayd.IbanBic('CZ5855000000001265098001', 'RZBCCZPP')
+s.amount = decimal.Decimal('250.00')
+s.due_date = datetime.date(2023, 10, 31)
+s.alternative_accounts = [spayd.IbanBic('CZ5855000000001265098001')]
+
+assert s.account.iban == 'CZ5855000000001265098001'
+assert s.account.bic == 'RZBCCZPP'
+assert s.amount == decimal.Decimal('250.00')
+assert s.due_date == datetime.date(2023, 10, 31)
+assert s.field('DT') == '20231031'
+assert [str(a) for a in s.alternative_accounts] == ['CZ5855000000001265098001']
+s.validate()
+");
+    }
+}
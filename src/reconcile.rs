@@ -0,0 +1,592 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::domestic::same_account;
+use crate::{fields, Spayd};
+
+/// Currency of payments without a CC field.
+const DEFAULT_CURRENCY: &str = "CZK";
+
+/// Default minimum confidence for a match.
+const DEFAULT_MIN_CONFIDENCE: u8 = 50;
+
+/// Whether money came into or left the account.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Direction {
+    /// Incoming payment.
+    #[default]
+    Credit,
+    /// Outgoing payment.
+    Debit,
+}
+
+/// A transaction from a bank statement.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Transaction {
+    /// The bank's identifier of the transaction, used to spot transactions
+    /// which are listed twice.
+    pub id: Option<String>,
+    /// Whether the transaction is incoming or outgoing.
+    pub direction: Direction,
+    /// Amount of the transaction in minor units (hundredths of the currency
+    /// unit), e.g. 48050 for 480.50. Unlike `Amount` this isn't limited to
+    /// what fits in the AM field.
+    pub amount: u64,
+    /// ISO 4217 currency code.
+    pub currency: String,
+    /// Booking date.
+    pub date: NaiveDate,
+    /// The statement's own account, as an IBAN or Czech domestic number.
+    pub account: Option<String>,
+    /// The other party's account, as an IBAN or Czech domestic number.
+    pub counterparty: Option<String>,
+    /// Czech variable symbol.
+    pub variable_symbol: Option<String>,
+    /// Czech specific symbol.
+    pub specific_symbol: Option<String>,
+    /// Czech constant symbol.
+    pub constant_symbol: Option<String>,
+    /// Payment reference, such as an end-to-end ID or creditor reference.
+    pub reference: Option<String>,
+    /// Message or remittance information.
+    pub message: Option<String>,
+}
+
+impl Transaction {
+    /// An incoming transaction with only an amount in minor units, currency
+    /// and date.
+    pub fn new(amount: u64, currency: &str, date: NaiveDate) -> Self {
+        Self {
+            amount,
+            currency: currency.into(),
+            date,
+            ..Self::default()
+        }
+    }
+
+    /// Fill in missing Czech symbols written in free text, e.g.
+    /// "/VS/1001/SS/22/KS/0308" or "VS: 1001".
+    pub fn fill_symbols_from(&mut self, text: &str) {
+        let targets = [
+            ("VS", &mut self.variable_symbol),
+            ("SS", &mut self.specific_symbol),
+            ("KS", &mut self.constant_symbol),
+        ];
+        for (name, symbol) in targets {
+            if symbol.is_none() {
+                *symbol = find_symbol(text, name);
+            }
+        }
+    }
+
+    /// Check if the transaction pays an issued payment exactly: it's incoming,
+    /// the receiving account (if known), amount, currency and variable symbol
+    /// all agree. Czech domestic account numbers are compared with IBANs by
+    /// their prefix, number and bank code.
+    pub fn satisfies(&self, payment: &Spayd) -> bool {
+        let account_matches = match (&self.account, payment.field(fields::ACCOUNT)) {
+            (Some(received), Some(account)) => same_account(received, account),
+            _ => true,
+        };
+        let symbol_matches = match payment.field(fields::VARIABLE_SYMBOL) {
+            Some(expected) => self
+                .variable_symbol
+                .as_deref()
+                .is_some_and(|symbol| same_symbol(expected, symbol)),
+            None => true,
+        };
+
+        self.direction == Direction::Credit
+            && payment.field(fields::CURRENCY).unwrap_or(DEFAULT_CURRENCY) == self.currency
+            && expected_amount(payment) == Some(self.amount)
+            && account_matches
+            && symbol_matches
+    }
+}
+
+/// Find the digits following a symbol name such as "VS" in free text. The
+/// name must start a word and be followed by up to two separators.
+fn find_symbol(text: &str, name: &str) -> Option<String> {
+    let upper = text.to_ascii_uppercase();
+    let bytes = upper.as_bytes();
+    upper.match_indices(name).find_map(|(start, _)| {
+        if start > 0 && bytes[start - 1].is_ascii_alphanumeric() {
+            return None;
+        }
+        let rest = &upper[start + name.len()..];
+        let trimmed = rest.trim_start_matches([':', '/', '=', ' ', '.']);
+        if rest.len() - trimmed.len() > 2 {
+            return None;
+        }
+        let digits: String = trimmed.chars().take_while(char::is_ascii_digit).collect();
+        if (1..=10).contains(&digits.len()) {
+            Some(digits)
+        } else {
+            None
+        }
+    })
+}
+
+/// How a matched transaction relates to the payment it was matched to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MatchKind {
+    /// The transaction pays the whole amount, or the payment has no amount.
+    Exact,
+    /// The transaction pays less than the amount still outstanding. Several
+    /// partial payments may add up to the full amount.
+    Partial,
+    /// The transaction pays more than the amount still outstanding.
+    Overpayment,
+    /// The payment was already paid in full, or the same transaction has
+    /// already been matched.
+    Duplicate,
+}
+
+/// A transaction matched to an issued payment.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Match {
+    /// Index of the transaction.
+    pub transaction: usize,
+    /// Index of the issued payment.
+    pub issued: usize,
+    /// How confident the match is, from 0 to 100.
+    pub confidence: u8,
+    /// How the transaction relates to the payment.
+    pub kind: MatchKind,
+}
+
+/// Whether an issued payment has been paid.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SettlementStatus {
+    /// No transactions were matched.
+    Unpaid,
+    /// The matched transactions add up to the amount, or the payment has no
+    /// amount and at least one transaction was matched.
+    Paid,
+    /// The matched transactions add up to less than the amount.
+    Underpaid,
+    /// The matched transactions add up to more than the amount.
+    Overpaid,
+}
+
+/// The total paid for an issued payment.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Settlement {
+    /// Index of the issued payment.
+    pub issued: usize,
+    /// The amount requested in minor units, if the payment has one.
+    pub expected: Option<u64>,
+    /// The total of the matched transactions in minor units, not counting
+    /// duplicates.
+    pub paid: u64,
+    /// Whether the payment has been paid.
+    pub status: SettlementStatus,
+}
+
+/// The result of reconciling transactions with issued payments.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Reconciliation {
+    /// Matched transactions, in the order they were booked.
+    pub matches: Vec<Match>,
+    /// Indexes of incoming transactions which didn't match any payment.
+    pub unmatched: Vec<usize>,
+    /// The settlement of every issued payment, in the order given.
+    pub settlements: Vec<Settlement>,
+}
+
+/// Matches incoming transactions to issued payments.
+///
+/// Each incoming transaction is scored against every issued payment and
+/// matched to the best one if the score reaches the minimum confidence.
+/// The currency, and the receiving account if the transaction has one, must
+/// always agree. The score is made up of:
+///
+/// | Agreement                                 | Points |
+/// |-------------------------------------------|--------|
+/// | Variable symbol (X-VS)                    | 50     |
+/// | Amount (AM)                               | 25     |
+/// | Reference (RF) in reference or message    | 15     |
+/// | Receiving account (ACC)                   | 10     |
+/// | Message (MSG) in message                  | 10     |
+/// | Specific symbol (X-SS)                    | 5      |
+/// | Constant symbol (X-KS)                    | 5      |
+///
+/// Transactions are processed in date order, so partial payments are added
+/// up until the full amount is paid.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Reconciler {
+    min_confidence: u8,
+}
+
+impl Default for Reconciler {
+    fn default() -> Self {
+        Self {
+            min_confidence: DEFAULT_MIN_CONFIDENCE,
+        }
+    }
+}
+
+/// Compare symbols, ignoring leading zeros.
+fn same_symbol(a: &str, b: &str) -> bool {
+    a.trim_start_matches('0') == b.trim_start_matches('0')
+}
+
+fn contains_ignoring_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+impl Reconciler {
+    /// A reconciler with a minimum confidence of 50.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the minimum confidence for a match, from 0 to 100.
+    pub fn with_min_confidence(mut self, min_confidence: u8) -> Self {
+        self.min_confidence = min_confidence.min(100);
+        self
+    }
+
+    /// Score a transaction against an issued payment. Returns `None` if they
+    /// can't match at all.
+    pub fn confidence(&self, payment: &Spayd, transaction: &Transaction) -> Option<u8> {
+        let currency = payment.field(fields::CURRENCY).unwrap_or(DEFAULT_CURRENCY);
+        if transaction.direction != Direction::Credit || currency != transaction.currency {
+            return None;
+        }
+
+        let mut score = 0;
+        if let (Some(account), Some(received)) =
+            (payment.field(fields::ACCOUNT), &transaction.account)
+        {
+            if !same_account(account, received) {
+                return None;
+            }
+            score += 10;
+        }
+
+        let symbols = [
+            (fields::VARIABLE_SYMBOL, &transaction.variable_symbol, 50),
+            (fields::SPECIFIC_SYMBOL, &transaction.specific_symbol, 5),
+            (fields::CONSTANT_SYMBOL, &transaction.constant_symbol, 5),
+        ];
+        for (field, symbol, points) in symbols {
+            if let (Some(expected), Some(symbol)) = (payment.field(field), symbol) {
+                if same_symbol(expected, symbol) {
+                    score += points;
+                }
+            }
+        }
+
+        if expected_amount(payment) == Some(transaction.amount) {
+            score += 25;
+        }
+
+        let message = transaction.message.as_deref().unwrap_or_default();
+        if let Some(reference) = payment.field(fields::REFERENCE) {
+            if transaction.reference.as_deref() == Some(reference) || message.contains(reference) {
+                score += 15;
+            }
+        }
+        if let Some(expected) = payment.field(fields::MESSAGE) {
+            if !expected.is_empty() && contains_ignoring_case(message, expected) {
+                score += 10;
+            }
+        }
+
+        Some(score.min(100))
+    }
+
+    /// Match transactions to issued payments.
+    pub fn reconcile(&self, issued: &[Spayd], transactions: &[Transaction]) -> Reconciliation {
+        let mut result = Reconciliation {
+            settlements: issued
+                .iter()
+                .enumerate()
+                .map(|(index, payment)| Settlement {
+                    issued: index,
+                    expected: expected_amount(payment),
+                    paid: 0,
+                    status: SettlementStatus::Unpaid,
+                })
+                .collect(),
+            ..Reconciliation::default()
+        };
+
+        let mut order: Vec<usize> = (0..transactions.len())
+            .filter(|&i| transactions[i].direction == Direction::Credit)
+            .collect();
+        order.sort_by_key(|&i| transactions[i].date);
+
+        // Issued payment matched by each transaction ID seen so far
+        let mut seen: HashMap<&str, Option<usize>> = HashMap::new();
+
+        for index in order {
+            let transaction = &transactions[index];
+
+            if let Some(id) = transaction.id.as_deref() {
+                if let Some(previous) = seen.get(id) {
+                    match previous {
+                        Some(issued) => result.matches.push(Match {
+                            transaction: index,
+                            issued: *issued,
+                            confidence: 100,
+                            kind: MatchKind::Duplicate,
+                        }),
+                        None => result.unmatched.push(index),
+                    }
+                    continue;
+                }
+            }
+
+            let best = issued
+                .iter()
+                .enumerate()
+                .filter_map(|(i, payment)| Some((i, self.confidence(payment, transaction)?)))
+                .filter(|(_, confidence)| *confidence >= self.min_confidence)
+                .max_by_key(|(i, confidence)| {
+                    let open = result.settlements[*i].status != SettlementStatus::Paid;
+                    (*confidence, open, std::cmp::Reverse(*i))
+                });
+
+            if let Some(id) = transaction.id.as_deref() {
+                seen.insert(id, best.map(|(i, _)| i));
+            }
+            let Some((issued, confidence)) = best else {
+                result.unmatched.push(index);
+                continue;
+            };
+
+            let settlement = &mut result.settlements[issued];
+            let kind = settle(settlement, transaction.amount);
+            result.matches.push(Match {
+                transaction: index,
+                issued,
+                confidence,
+                kind,
+            });
+        }
+
+        result
+    }
+}
+
+/// Add a payment to a settlement, returning how it relates to the amount
+/// still outstanding.
+fn settle(settlement: &mut Settlement, amount: u64) -> MatchKind {
+    let Some(expected) = settlement.expected else {
+        settlement.paid = settlement.paid.saturating_add(amount);
+        settlement.status = SettlementStatus::Paid;
+        return MatchKind::Exact;
+    };
+    if settlement.paid >= expected {
+        return MatchKind::Duplicate;
+    }
+
+    let previous = settlement.paid;
+    settlement.paid = previous.saturating_add(amount);
+    settlement.status = match settlement.paid.cmp(&expected) {
+        std::cmp::Ordering::Less => SettlementStatus::Underpaid,
+        std::cmp::Ordering::Equal => SettlementStatus::Paid,
+        std::cmp::Ordering::Greater => SettlementStatus::Overpaid,
+    };
+    match settlement.status {
+        SettlementStatus::Paid if previous == 0 => MatchKind::Exact,
+        SettlementStatus::Overpaid => MatchKind::Overpayment,
+        _ => MatchKind::Partial,
+    }
+}
+
+/// The fixed amount of an issued payment in minor units.
+fn expected_amount(payment: &Spayd) -> Option<u64> {
+    payment
+        .fixed_amount()
+        .ok()
+        .map(|amount| amount.minor_units())
+}
+
+/// Match transactions to issued payments with the default settings.
+pub fn reconcile(issued: &[Spayd], transactions: &[Transaction]) -> Reconciliation {
+    Reconciler::new().reconcile(issued, transactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::parse_minor_units;
+
+    const ACCOUNT: &str = "CZ5855000000001265098001";
+
+    fn invoice(vs: &str, amount: &str) -> Spayd {
+        Spayd::new_v1_0(vec![
+            ("ACC", ACCOUNT),
+            ("AM", amount),
+            ("CC", "CZK"),
+            ("X-VS", vs),
+        ])
+    }
+
+    fn payment(vs: &str, amount: &str, day: u32) -> Transaction {
+        Transaction {
+            variable_symbol: Some(vs.into()),
+            ..Transaction::new(
+                parse_minor_units(amount).unwrap(),
+                "CZK",
+                NaiveDate::from_ymd_opt(2026, 10, day).unwrap(),
+            )
+        }
+    }
+
+    #[test]
+    fn symbols_in_text() {
+        let mut transaction = Transaction::default();
+        transaction.fill_symbols_from("/VS/1001/SS/22/KS/0308");
+        assert_eq!(transaction.variable_symbol.as_deref(), Some("1001"));
+        assert_eq!(transaction.specific_symbol.as_deref(), Some("22"));
+        assert_eq!(transaction.constant_symbol.as_deref(), Some("0308"));
+
+        let mut transaction = Transaction::default();
+        transaction.fill_symbols_from("Invoice VS: 2026001236, KS:0008 thanks");
+        assert_eq!(transaction.variable_symbol.as_deref(), Some("2026001236"));
+        assert_eq!(transaction.constant_symbol.as_deref(), Some("0008"));
+        assert_eq!(transaction.specific_symbol, None);
+
+        let mut transaction = Transaction::default();
+        transaction.fill_symbols_from("CLASS 12345678901");
+        assert_eq!(transaction.specific_symbol, None);
+        assert_eq!(transaction.variable_symbol, None);
+    }
+
+    #[test]
+    fn satisfies_payment() {
+        let issued = invoice("1001", "500.00");
+        let mut transaction = payment("0001001", "500.00", 1);
+        transaction.account = Some("1265098001/5500".into());
+        assert!(transaction.satisfies(&issued));
+
+        let mut wrong_amount = transaction.clone();
+        wrong_amount.amount = 49_900;
+        assert!(!wrong_amount.satisfies(&issued));
+
+        let mut no_symbol = transaction.clone();
+        no_symbol.variable_symbol = None;
+        assert!(!no_symbol.satisfies(&issued));
+
+        transaction.account = Some("19-2000145399/0800".into());
+        assert!(!transaction.satisfies(&issued));
+    }
+
+    #[test]
+    fn exact_payment() {
+        let issued = [invoice("1001", "500.00"), invoice("1002", "250.00")];
+        let result = reconcile(&issued, &[payment("0000001002", "250.00", 1)]);
+
+        assert_eq!(
+            result.matches,
+            vec![Match {
+                transaction: 0,
+                issued: 1,
+                confidence: 75,
+                kind: MatchKind::Exact
+            }]
+        );
+        assert_eq!(result.settlements[0].status, SettlementStatus::Unpaid);
+        assert_eq!(result.settlements[1].status, SettlementStatus::Paid);
+    }
+
+    #[test]
+    fn partial_payments() {
+        let issued = [invoice("1001", "500.00")];
+        let transactions = [payment("1001", "300.00", 2), payment("1001", "200.00", 1)];
+        let result = reconcile(&issued, &transactions);
+
+        let kinds: Vec<_> = result
+            .matches
+            .iter()
+            .map(|m| (m.transaction, m.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![(1, MatchKind::Partial), (0, MatchKind::Partial)]
+        );
+        assert_eq!(result.settlements[0].paid, 50_000);
+        assert_eq!(result.settlements[0].status, SettlementStatus::Paid);
+    }
+
+    #[test]
+    fn under_and_over_payment() {
+        let issued = [invoice("1001", "500.00"), invoice("1002", "500.00")];
+        let transactions = [payment("1001", "400.00", 1), payment("1002", "600.00", 1)];
+        let result = reconcile(&issued, &transactions);
+
+        assert_eq!(result.matches[0].kind, MatchKind::Partial);
+        assert_eq!(result.matches[1].kind, MatchKind::Overpayment);
+        assert_eq!(result.settlements[0].status, SettlementStatus::Underpaid);
+        assert_eq!(result.settlements[1].status, SettlementStatus::Overpaid);
+    }
+
+    #[test]
+    fn duplicates() {
+        let issued = [invoice("1001", "500.00")];
+        let mut first = payment("1001", "500.00", 1);
+        first.id = Some("TX1".into());
+        let listed_twice = first.clone();
+        let paid_twice = payment("1001", "500.00", 3);
+        let result = reconcile(&issued, &[first, listed_twice, paid_twice]);
+
+        let kinds: Vec<_> = result.matches.iter().map(|m| m.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![MatchKind::Exact, MatchKind::Duplicate, MatchKind::Duplicate]
+        );
+        assert_eq!(result.settlements[0].paid, 50_000);
+    }
+
+    #[test]
+    fn unmatched() {
+        let issued = [invoice("1001", "500.00")];
+        let mut wrong_currency = payment("1001", "500.00", 1);
+        wrong_currency.currency = "EUR".into();
+        let mut outgoing = payment("1001", "500.00", 1);
+        outgoing.direction = Direction::Debit;
+        let unknown = payment("9999", "123.00", 1);
+        let mut other_account = payment("1001", "500.00", 1);
+        other_account.account = Some("CZ6508000000192000145399".into());
+
+        let result = reconcile(&issued, &[wrong_currency, outgoing, unknown, other_account]);
+        assert!(result.matches.is_empty());
+        assert_eq!(result.unmatched, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn confidence_without_symbol() {
+        let mut issued = Spayd::new_v1_0(vec![
+            ("ACC", ACCOUNT),
+            ("AM", "99.00"),
+            ("RF", "7788"),
+            ("MSG", "Membership"),
+        ]);
+        let mut transaction =
+            Transaction::new(9_900, "CZK", NaiveDate::from_ymd_opt(2026, 10, 1).unwrap());
+        transaction.account = Some("CZ58 5500 0000 0012 6509 8001".into());
+        transaction.message = Some("membership 7788".into());
+
+        let reconciler = Reconciler::new();
+        assert_eq!(reconciler.confidence(&issued, &transaction), Some(60));
+        assert_eq!(
+            reconciler
+                .reconcile(&[issued.clone()], &[transaction.clone()])
+                .matches[0]
+                .kind,
+            MatchKind::Exact
+        );
+
+        issued.set_field("MSG", "Donation");
+        let strict = Reconciler::new().with_min_confidence(60);
+        assert!(strict
+            .reconcile(&[issued], &[transaction])
+            .matches
+            .is_empty());
+    }
+}
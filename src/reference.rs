@@ -0,0 +1,279 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::{fields, Spayd, SpaydError};
+
+/// Maximum length of the reference part of a creditor reference.
+const MAX_REFERENCE_LENGTH: usize = 21;
+
+/// Maximum number of digits in the SPAYD RF field.
+pub(crate) const MAX_SPAYD_REFERENCE_LENGTH: usize = 16;
+
+/// Maximum number of digits in a Czech variable symbol.
+pub(crate) const MAX_VARIABLE_SYMBOL_LENGTH: usize = 10;
+
+/// An ISO 11649 structured creditor reference, e.g. "RF18539007547034".
+///
+/// This is the form of reference used in SEPA payments and the structured
+/// remittance field of EPC QR codes. SPAYD's own RF field only allows digits,
+/// so a reference can be written there if its reference part is numeric and
+/// short enough (see `spayd_reference`).
+/// ```
+/// use spayd::CreditorReference;
+///
+/// let reference = CreditorReference::new("539007547034").unwrap();
+/// assert_eq!(reference.to_string(), "RF18539007547034");
+/// assert_eq!(reference.to_print_format(), "RF18 5390 0754 7034");
+/// assert_eq!(reference.spayd_reference(), Some("539007547034"));
+/// assert_eq!(reference.variable_symbol(), None);
+///
+/// let parsed: CreditorReference = "RF18 5390 0754 7034".parse().unwrap();
+/// assert_eq!(parsed, reference);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CreditorReference {
+    reference: String,
+    check_digits: u8,
+}
+
+/// The remainder of the text, read as a number with letters standing for
+/// 10 to 35, when divided by 97.
+pub(crate) fn mod97(text: &str) -> Option<u32> {
+    text.chars().try_fold(0, |remainder, c| {
+        let value = c.to_digit(36)?;
+        let remainder = if value < 10 {
+            remainder * 10 + value
+        } else {
+            remainder * 100 + value
+        };
+        Some(remainder % 97)
+    })
+}
+
+fn is_reference_part(text: &str) -> bool {
+    (1..=MAX_REFERENCE_LENGTH).contains(&text.len())
+        && text
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b.is_ascii_uppercase())
+}
+
+/// Check that text is between 1 and `max_length` ASCII digits.
+pub(crate) fn is_digits(text: &str, max_length: usize) -> bool {
+    (1..=max_length).contains(&text.len()) && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Check that text can be used in the SPAYD RF field: 1 to 16 digits.
+pub fn check_spayd_reference(text: &str) -> Result<(), SpaydError> {
+    if is_digits(text, MAX_SPAYD_REFERENCE_LENGTH) {
+        Ok(())
+    } else {
+        Err(SpaydError::ConvertError(text.into()))
+    }
+}
+
+impl CreditorReference {
+    /// Create a creditor reference by calculating the check digits for a
+    /// reference of 1 to 21 letters and digits. Lowercase letters are
+    /// converted to uppercase.
+    pub fn new(reference: &str) -> Result<Self, SpaydError> {
+        let error = || SpaydError::ConvertError(reference.into());
+        let normalized = reference.to_ascii_uppercase();
+        if !is_reference_part(&normalized) {
+            return Err(error());
+        }
+
+        let remainder = mod97(&format!("{}RF00", normalized)).ok_or_else(error)?;
+        Ok(Self {
+            reference: normalized,
+            check_digits: (98 - remainder) as u8,
+        })
+    }
+
+    /// The reference without the "RF" prefix and check digits.
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    /// The two check digits.
+    pub fn check_digits(&self) -> u8 {
+        self.check_digits
+    }
+
+    /// The reference in groups of four characters, as it's usually printed
+    /// on invoices.
+    pub fn to_print_format(&self) -> String {
+        let electronic = self.to_string();
+        electronic
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| String::from_utf8_lossy(chunk))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The reference part if it can be used in the SPAYD RF field.
+    pub fn spayd_reference(&self) -> Option<&str> {
+        Some(self.reference()).filter(|r| is_digits(r, MAX_SPAYD_REFERENCE_LENGTH))
+    }
+
+    /// The reference part if it can be used as a Czech variable symbol
+    /// (X-VS field).
+    pub fn variable_symbol(&self) -> Option<&str> {
+        Some(self.reference()).filter(|r| is_digits(r, MAX_VARIABLE_SYMBOL_LENGTH))
+    }
+}
+
+impl Display for CreditorReference {
+    /// The electronic format without spaces. This is also the value for the
+    /// structured remittance field of EPC QR codes.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RF{:02}{}", self.check_digits, self.reference)
+    }
+}
+
+impl FromStr for CreditorReference {
+    type Err = SpaydError;
+
+    /// Parse a reference in electronic or print format and check its check
+    /// digits.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || SpaydError::ConvertError(text.into());
+        let compact: String = text
+            .chars()
+            .filter(|c| *c != ' ')
+            .collect::<String>()
+            .to_ascii_uppercase();
+
+        let rest = compact.strip_prefix("RF").ok_or_else(error)?;
+        let (check_digits, reference) = rest.split_at_checked(2).ok_or_else(error)?;
+        if !is_digits(check_digits, 2) || !is_reference_part(reference) {
+            return Err(error());
+        }
+
+        let rearranged = format!("{}RF{}", reference, check_digits);
+        if mod97(&rearranged) != Some(1) {
+            return Err(error());
+        }
+        Ok(Self {
+            reference: reference.into(),
+            check_digits: check_digits.parse().or(Err(error()))?,
+        })
+    }
+}
+
+impl Spayd {
+    /// Check that the RF field, if present, only contains up to 16 digits.
+    pub fn check_reference(&self) -> Result<(), SpaydError> {
+        match self.field(fields::REFERENCE) {
+            Some(text) => check_spayd_reference(text),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the RF field as an ISO 11649 creditor reference, e.g. for a SEPA
+    /// payment or EPC QR code.
+    pub fn creditor_reference(&self) -> Result<CreditorReference, SpaydError> {
+        self.field_converted(fields::REFERENCE, |text| {
+            check_spayd_reference(text).and_then(|_| CreditorReference::new(text))
+        })
+    }
+
+    /// Set the RF field from a creditor reference. Fails if the reference
+    /// part isn't up to 16 digits.
+    pub fn set_creditor_reference(
+        &mut self,
+        reference: &CreditorReference,
+    ) -> Result<(), SpaydError> {
+        let text = reference
+            .spayd_reference()
+            .ok_or_else(|| SpaydError::ConvertError(reference.to_string()))?;
+        self.set_field(fields::REFERENCE, text.to_owned());
+        Ok(())
+    }
+
+    /// Set the X-VS field from a creditor reference. Fails if the reference
+    /// part isn't up to 10 digits.
+    pub fn set_variable_symbol_from(
+        &mut self,
+        reference: &CreditorReference,
+    ) -> Result<(), SpaydError> {
+        let text = reference
+            .variable_symbol()
+            .ok_or_else(|| SpaydError::ConvertError(reference.to_string()))?;
+        self.set_field(fields::VARIABLE_SYMBOL, text.to_owned());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate() {
+        let reference = CreditorReference::new("539007547034").unwrap();
+        assert_eq!(reference.check_digits(), 18);
+        assert_eq!(reference.to_string(), "RF18539007547034");
+
+        let reference = CreditorReference::new("abc123").unwrap();
+        assert_eq!(reference.reference(), "ABC123");
+        assert_eq!(reference.to_string().parse(), Ok(reference));
+    }
+
+    #[test]
+    fn generate_invalid() {
+        assert!(CreditorReference::new("").is_err());
+        assert!(CreditorReference::new("123-456").is_err());
+        assert!(CreditorReference::new("1234567890123456789012").is_err());
+    }
+
+    #[test]
+    fn parse() {
+        let expected = CreditorReference::new("539007547034").unwrap();
+        assert_eq!("RF18539007547034".parse(), Ok(expected.clone()));
+        assert_eq!("rf18 5390 0754 7034".parse(), Ok(expected));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for text in ["RF19539007547034", "XX18539007547034", "RF18", "RF1X5390"] {
+            assert_eq!(
+                text.parse::<CreditorReference>(),
+                Err(SpaydError::ConvertError(text.into())),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn spayd_reference_check() {
+        assert_eq!(check_spayd_reference("1234567890123456"), Ok(()));
+        assert!(check_spayd_reference("12345678901234567").is_err());
+        assert!(check_spayd_reference("ABC").is_err());
+        assert!(check_spayd_reference("").is_err());
+
+        let spayd = Spayd::new_v1_0(vec![("RF", "INV-1")]);
+        assert_eq!(
+            spayd.check_reference(),
+            Err(SpaydError::ConvertError("INV-1".into()))
+        );
+        assert_eq!(Spayd::empty_v1_0().check_reference(), Ok(()));
+    }
+
+    #[test]
+    fn spayd_conversions() {
+        let invoice = CreditorReference::new("2026001234").unwrap();
+        let mut spayd = Spayd::empty_v1_0();
+        spayd.set_creditor_reference(&invoice).unwrap();
+        spayd.set_variable_symbol_from(&invoice).unwrap();
+
+        assert_eq!(spayd.field("RF"), Some("2026001234"));
+        assert_eq!(spayd.field("X-VS"), Some("2026001234"));
+        assert_eq!(spayd.creditor_reference(), Ok(invoice));
+
+        let long = CreditorReference::new("12345678901234567").unwrap();
+        assert!(spayd.set_creditor_reference(&long).is_err());
+        assert!(spayd.set_variable_symbol_from(&long).is_err());
+        assert_eq!(spayd.field("RF"), Some("2026001234"));
+    }
+}
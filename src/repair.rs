@@ -0,0 +1,315 @@
+use crate::parser::parse_unvalidated;
+use crate::{fields, Spayd, SpaydError};
+
+/// Characters which OCR commonly confuses, in both directions.
+const DEFAULT_CONFUSIONS: &[(char, char)] = &[
+    ('O', '0'),
+    ('o', '0'),
+    ('I', '1'),
+    ('l', '1'),
+    ('S', '5'),
+    ('B', '8'),
+    ('Z', '2'),
+    ('G', '6'),
+];
+
+/// A character replaced in a field value.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Substitution {
+    /// The field whose value was changed.
+    pub field: String,
+    /// The position of the character in the value, counted in characters.
+    pub position: usize,
+    /// The character which was read.
+    pub from: char,
+    /// The character it was replaced with.
+    pub to: char,
+}
+
+/// A corrected SPAYD which passes its CRC32 check.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Crc32Repair {
+    /// The corrected SPAYD.
+    pub spayd: Spayd,
+    /// The characters which were replaced, empty if the SPAYD already
+    /// passed the check.
+    pub changes: Vec<Substitution>,
+}
+
+/// Repairs SPAYDs damaged by OCR using their CRC32 checksum.
+///
+/// Each character of the field values (including the CRC32 value itself)
+/// which appears in the confusion table is replaced by the characters it
+/// may have been confused with, and the CRC32 check is repeated. Single
+/// substitutions are tried first and pairs of substitutions only if no
+/// single one passes. Every candidate passing the check and validation is
+/// returned, as unlikely as several matches are.
+///
+/// Field names aren't changed.
+/// ```
+/// use spayd::Crc32Repairer;
+///
+/// // The 1 of the amount was read as I.
+/// let scanned = "SPD*1.0*ACC:CZ5855000000001265098001*AM:I00.00*CC:CZK*CRC32:AAD80227";
+/// assert!(scanned.parse::<spayd::Spayd>().is_err());
+///
+/// let repairs = Crc32Repairer::new().repair_str(scanned).unwrap();
+/// assert_eq!(repairs.len(), 1);
+/// assert_eq!(repairs[0].spayd.field("AM"), Some("100.00"));
+/// assert_eq!(repairs[0].changes[0].field, "AM");
+/// assert_eq!((repairs[0].changes[0].from, repairs[0].changes[0].to), ('I', '1'));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Crc32Repairer {
+    confusions: Vec<(char, char)>,
+    max_changes: usize,
+}
+
+impl Default for Crc32Repairer {
+    fn default() -> Self {
+        Self {
+            confusions: DEFAULT_CONFUSIONS.to_vec(),
+            max_changes: 2,
+        }
+    }
+}
+
+/// One way of changing one character.
+struct Replacement {
+    field: usize,
+    position: usize,
+    from: char,
+    to: char,
+}
+
+impl Crc32Repairer {
+    /// A repairer using the default confusion table (O/0, o/0, I/1, l/1,
+    /// S/5, B/8, Z/2 and G/6) and up to two substitutions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also try replacing each of the characters with the other.
+    pub fn with_confusion(mut self, a: char, b: char) -> Self {
+        self.confusions.push((a, b));
+        self
+    }
+
+    /// Use only the given confusions instead of the default table.
+    pub fn with_confusions(mut self, confusions: &[(char, char)]) -> Self {
+        self.confusions = confusions.to_vec();
+        self
+    }
+
+    /// The maximum number of characters to replace, 1 or 2. Larger values
+    /// are treated as 2, as the number of candidates grows quickly.
+    pub fn with_max_changes(mut self, max_changes: usize) -> Self {
+        self.max_changes = max_changes.clamp(1, 2);
+        self
+    }
+
+    fn alternatives(&self, c: char) -> impl Iterator<Item = char> + '_ {
+        self.confusions.iter().filter_map(move |&(a, b)| {
+            if c == a {
+                Some(b)
+            } else if c == b {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Parse text without validating it and repair the result. Fails if
+    /// the text isn't a syntactically valid SPAYD.
+    pub fn repair_str(&self, text: &str) -> Result<Vec<Crc32Repair>, SpaydError> {
+        self.repair(&parse_unvalidated(text)?)
+    }
+
+    /// Find corrections which make the SPAYD pass its CRC32 check. Fails
+    /// with `FieldMissing` if it has no CRC32 field. An empty list means no
+    /// correction was found. Corrections which give a field that can't be
+    /// set (see `Spayd::try_set_field`), e.g. because a confusion produces a
+    /// control character, are skipped.
+    pub fn repair(&self, spayd: &Spayd) -> Result<Vec<Crc32Repair>, SpaydError> {
+        if spayd.field(fields::CRC32_CHECKSUM).is_none() {
+            return Err(SpaydError::FieldMissing(fields::CRC32_CHECKSUM.into()));
+        }
+        if spayd.validate().is_ok() {
+            return Ok(vec![Crc32Repair {
+                spayd: spayd.clone(),
+                changes: Vec::new(),
+            }]);
+        }
+
+        let values: Vec<(String, Vec<char>)> = spayd
+            .iter()
+            .map(|(key, value)| (key.to_owned(), value.chars().collect()))
+            .collect();
+        let mut options = Vec::new();
+        for (field, (_, value)) in values.iter().enumerate() {
+            for (position, c) in value.iter().enumerate() {
+                options.extend(self.alternatives(*c).map(|to| Replacement {
+                    field,
+                    position,
+                    from: *c,
+                    to,
+                }));
+            }
+        }
+
+        let mut repairs = Vec::new();
+        for option in &options {
+            repairs.extend(self.check(spayd, &values, &[option]));
+        }
+        if repairs.is_empty() && self.max_changes >= 2 {
+            for (index, first) in options.iter().enumerate() {
+                for second in &options[index + 1..] {
+                    if (second.field, second.position) != (first.field, first.position) {
+                        repairs.extend(self.check(spayd, &values, &[first, second]));
+                    }
+                }
+            }
+        }
+        Ok(repairs)
+    }
+
+    /// Apply substitutions and keep the result if it's valid.
+    fn check(
+        &self,
+        spayd: &Spayd,
+        values: &[(String, Vec<char>)],
+        options: &[&Replacement],
+    ) -> Option<Crc32Repair> {
+        let mut candidate = spayd.clone();
+        let mut changes = Vec::new();
+        for option in options {
+            let (key, value) = &values[option.field];
+            let mut value = match candidate.field(key) {
+                Some(current) => current.chars().collect(),
+                None => value.clone(),
+            };
+            value[option.position] = option.to;
+            candidate
+                .try_set_field(key.as_str(), value.into_iter().collect::<String>())
+                .ok()?;
+            changes.push(Substitution {
+                field: key.clone(),
+                position: option.position,
+                from: option.from,
+                to: option.to,
+            });
+        }
+        candidate.validate().ok().map(|_| Crc32Repair {
+            spayd: candidate,
+            changes,
+        })
+    }
+}
+
+impl Spayd {
+    /// Find corrections for OCR errors which make the SPAYD pass its CRC32
+    /// check, using the default `Crc32Repairer`.
+    pub fn repair_crc32(&self) -> Result<Vec<Crc32Repair>, SpaydError> {
+        Crc32Repairer::new().repair(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// CRC32 of ACC:CZ5855000000001265098001, AM:100.00 and CC:CZK.
+    const CODE: &str = "SPD*1.0*ACC:CZ5855000000001265098001*AM:100.00*CC:CZK*CRC32:AAD80227";
+
+    fn changes(repair: &Crc32Repair) -> Vec<(&str, usize, char, char)> {
+        repair
+            .changes
+            .iter()
+            .map(|c| (c.field.as_str(), c.position, c.from, c.to))
+            .collect()
+    }
+
+    #[test]
+    fn valid_code() {
+        let repairs = Crc32Repairer::new().repair_str(CODE).unwrap();
+        assert_eq!(repairs.len(), 1);
+        assert!(repairs[0].changes.is_empty());
+    }
+
+    #[test]
+    fn single_substitution() {
+        let damaged = CODE.replace("AM:100.00", "AM:1O0.00");
+        let repairs = Crc32Repairer::new().repair_str(&damaged).unwrap();
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(repairs[0].spayd, CODE.parse().unwrap());
+        assert_eq!(changes(&repairs[0]), vec![("AM", 1, 'O', '0')]);
+    }
+
+    #[test]
+    fn damaged_checksum() {
+        let damaged = CODE.replace("CRC32:AAD80227", "CRC32:AAD8O227");
+        let repairs = damaged.parse::<Spayd>();
+        assert_eq!(repairs, Err(SpaydError::Crc32Failed));
+
+        let repairs = Crc32Repairer::new().repair_str(&damaged).unwrap();
+        assert_eq!(changes(&repairs[0]), vec![("CRC32", 4, 'O', '0')]);
+    }
+
+    #[test]
+    fn double_substitution() {
+        let damaged = CODE.replace("CZ5855", "CZS8S5");
+        let repairer = Crc32Repairer::new();
+        let repairs = repairer.repair_str(&damaged).unwrap();
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(
+            changes(&repairs[0]),
+            vec![("ACC", 2, 'S', '5'), ("ACC", 4, 'S', '5')]
+        );
+
+        let single = repairer.with_max_changes(1).repair_str(&damaged).unwrap();
+        assert!(single.is_empty());
+    }
+
+    #[test]
+    fn custom_confusions() {
+        let damaged = CODE.replace("CC:CZK", "CC:CZX");
+        assert!(Crc32Repairer::new()
+            .repair_str(&damaged)
+            .unwrap()
+            .is_empty());
+
+        let repairs = Crc32Repairer::new()
+            .with_confusions(&[])
+            .with_confusion('X', 'K')
+            .repair_str(&damaged)
+            .unwrap();
+        assert_eq!(changes(&repairs[0]), vec![("CC", 2, 'X', 'K')]);
+    }
+
+    #[test]
+    fn invalid_substitution() {
+        let damaged = CODE.replace("CC:CZK", "CC:CZX");
+        let repairer = Crc32Repairer::new()
+            .with_confusions(&[])
+            .with_confusion('X', '\n')
+            .with_confusion('X', 'K');
+        let repairs = repairer.repair_str(&damaged).unwrap();
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(changes(&repairs[0]), vec![("CC", 2, 'X', 'K')]);
+
+        let mut spayd: Spayd = CODE.parse().unwrap();
+        spayd.set_field("x-note", "1");
+        assert_eq!(spayd.repair_crc32(), Ok(vec![]));
+    }
+
+    #[test]
+    fn missing_checksum() {
+        let spayd = Spayd::new_v1_0(vec![("ACC", "CZ5855000000001265098001")]);
+        assert_eq!(
+            spayd.repair_crc32(),
+            Err(SpaydError::FieldMissing("CRC32".into()))
+        );
+        assert!(Crc32Repairer::new().repair_str("SPD*1.0*").is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use iso_currency::Currency;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::amount::{MAX_DECIMALS, MAX_LENGTH};
+use crate::{fields, Spayd, SpaydError};
+
+/// How to round amounts with more decimal places than the currency allows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RoundingMode {
+    /// Round to the nearest minor unit, with ties going to the even
+    /// neighbour (banker's rounding).
+    HalfEven,
+    /// Round to the nearest minor unit, with ties going away from zero.
+    HalfUp,
+    /// Round to whole major units, with ties going away from zero. This
+    /// matches cash payments in currencies without coins for minor units,
+    /// such as CZK.
+    Cash,
+}
+
+/// The number of decimal places an amount may have in the given currency.
+/// This is the currency's ISO 4217 exponent, limited to the 2 decimal
+/// places allowed in the AM field. Currencies without an exponent (such as
+/// precious metals) use the AM field's limit.
+pub fn currency_decimals(currency: Currency) -> u32 {
+    currency.exponent().map_or(MAX_DECIMALS, |exponent| {
+        u32::from(exponent).min(MAX_DECIMALS)
+    })
+}
+
+/// Round an amount for the given currency.
+pub fn round_amount(amount: &Decimal, currency: Currency, mode: RoundingMode) -> Decimal {
+    let decimals = currency_decimals(currency);
+    let rounded = match mode {
+        RoundingMode::HalfEven => {
+            amount.round_dp_with_strategy(decimals, RoundingStrategy::MidpointNearestEven)
+        }
+        RoundingMode::HalfUp => {
+            amount.round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero)
+        }
+        RoundingMode::Cash => {
+            amount.round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+        }
+    };
+    with_scale(rounded, decimals)
+}
+
+/// Set the number of decimal places shown without changing the value.
+fn with_scale(mut amount: Decimal, decimals: u32) -> Decimal {
+    amount.rescale(decimals);
+    amount
+}
+
+/// Check that an amount has no more decimal places than the currency allows.
+fn check_precision(amount: &Decimal, currency: Currency) -> Result<(), SpaydError> {
+    let decimals = currency_decimals(currency);
+    if amount.normalize().scale() > decimals {
+        Err(SpaydError::AmountPrecision {
+            amount: amount.to_string(),
+            currency: currency.code().into(),
+            decimals,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+impl Spayd {
+    /// Write an amount which is already valid for its currency.
+    fn write_amount(&mut self, amount: Decimal, currency: Currency) -> Result<(), SpaydError> {
+        let text = with_scale(amount, currency_decimals(currency)).to_string();
+        if amount.is_sign_negative() || text.len() > MAX_LENGTH {
+            return Err(SpaydError::ConvertError(text));
+        }
+        self.set_field(fields::AMOUNT, text);
+        Ok(())
+    }
+
+    /// Set the amount without rounding, using the currency in the CC field.
+    /// Fails with `AmountPrecision` if the amount has more decimal places
+    /// than the currency allows. The amount is written with the currency's
+    /// number of decimal places, e.g. "480.50" for CZK or "1000" for JPY.
+    pub fn set_amount_exact(&mut self, amount: &Decimal) -> Result<(), SpaydError> {
+        let currency = self.currency()?;
+        check_precision(amount, currency)?;
+        self.write_amount(*amount, currency)
+    }
+
+    /// Round the amount for the currency in the CC field and set it.
+    /// Returns the amount which was written.
+    pub fn set_amount_rounded(
+        &mut self,
+        amount: &Decimal,
+        mode: RoundingMode,
+    ) -> Result<Decimal, SpaydError> {
+        let currency = self.currency()?;
+        let rounded = round_amount(amount, currency, mode);
+        self.write_amount(rounded, currency)?;
+        Ok(rounded)
+    }
+
+    /// Check that the amount has no more decimal places than the currency
+    /// in the CC field allows. Both fields must be present.
+    pub fn check_amount_precision(&self) -> Result<(), SpaydError> {
+        check_precision(&self.amount()?, self.currency()?)
+    }
+
+    /// Check the amount's precision if both the amount and currency are
+    /// present and readable. Used by `validate`.
+    pub(crate) fn check_amount_precision_if_present(&self) -> Result<(), SpaydError> {
+        match (self.amount(), self.currency()) {
+            (Ok(amount), Ok(currency)) => check_precision(&amount, currency),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payment(currency: &str) -> Spayd {
+        Spayd::new_v1_0(vec![("ACC", "CZ5855000000001265098001"), ("CC", currency)])
+    }
+
+    #[test]
+    fn decimals() {
+        assert_eq!(currency_decimals(Currency::CZK), 2);
+        assert_eq!(currency_decimals(Currency::JPY), 0);
+        assert_eq!(currency_decimals(Currency::KWD), 2);
+        assert_eq!(currency_decimals(Currency::XAU), 2);
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let amount = Decimal::new(1005, 3);
+        assert_eq!(
+            round_amount(&amount, Currency::CZK, RoundingMode::HalfEven),
+            Decimal::new(100, 2)
+        );
+        assert_eq!(
+            round_amount(&amount, Currency::CZK, RoundingMode::HalfUp),
+            Decimal::new(101, 2)
+        );
+        assert_eq!(
+            round_amount(&Decimal::new(48050, 2), Currency::CZK, RoundingMode::Cash),
+            Decimal::new(48100, 2)
+        );
+        assert_eq!(
+            round_amount(
+                &Decimal::new(10005, 1),
+                Currency::JPY,
+                RoundingMode::HalfEven
+            ),
+            Decimal::new(1000, 0)
+        );
+    }
+
+    #[test]
+    fn set_rounded() {
+        let mut spayd = payment("CZK");
+        assert_eq!(
+            spayd.set_amount_rounded(&Decimal::new(1005, 3), RoundingMode::HalfUp),
+            Ok(Decimal::new(101, 2))
+        );
+        assert_eq!(spayd.field("AM"), Some("1.01"));
+
+        spayd
+            .set_amount_rounded(&Decimal::new(250, 0), RoundingMode::HalfEven)
+            .unwrap();
+        assert_eq!(spayd.field("AM"), Some("250.00"));
+
+        let mut spayd = payment("JPY");
+        spayd
+            .set_amount_rounded(&Decimal::new(12345, 1), RoundingMode::HalfUp)
+            .unwrap();
+        assert_eq!(spayd.field("AM"), Some("1235"));
+    }
+
+    #[test]
+    fn set_exact() {
+        let mut spayd = payment("JPY");
+        assert_eq!(
+            spayd.set_amount_exact(&Decimal::new(10005, 1)),
+            Err(SpaydError::AmountPrecision {
+                amount: "1000.5".into(),
+                currency: "JPY".into(),
+                decimals: 0
+            })
+        );
+        assert_eq!(spayd.field("AM"), None);
+
+        assert_eq!(spayd.set_amount_exact(&Decimal::new(100000, 2)), Ok(()));
+        assert_eq!(spayd.field("AM"), Some("1000"));
+    }
+
+    #[test]
+    fn set_requires_currency() {
+        let mut spayd = Spayd::empty_v1_0();
+        assert_eq!(
+            spayd.set_amount_exact(&Decimal::new(1, 0)),
+            Err(SpaydError::FieldMissing("CC".into()))
+        );
+    }
+
+    #[test]
+    fn set_invalid_amounts() {
+        let mut spayd = payment("CZK");
+        assert!(spayd.set_amount_exact(&Decimal::new(-1, 0)).is_err());
+        assert!(spayd
+            .set_amount_exact(&Decimal::new(100_000_000, 0))
+            .is_err());
+    }
+
+    #[test]
+    fn validate_precision() {
+        let spayd: Result<Spayd, _> =
+            "SPD*1.0*ACC:CZ5855000000001265098001*AM:100.50*CC:JPY".parse();
+        assert_eq!(
+            spayd,
+            Err(SpaydError::AmountPrecision {
+                amount: "100.50".into(),
+                currency: "JPY".into(),
+                decimals: 0
+            })
+        );
+
+        let spayd: Spayd = "SPD*1.0*ACC:CZ5855000000001265098001*AM:100.00*CC:JPY"
+            .parse()
+            .unwrap();
+        assert_eq!(spayd.check_amount_precision(), Ok(()));
+    }
+}
@@ -0,0 +1,284 @@
+use std::ops::Range;
+
+use crate::Spayd;
+
+/// The start of every SPAYD.
+const HEADER: &str = "SPD*";
+
+/// Characters removed from the end of a code, as they're more likely to be
+/// punctuation of the surrounding text.
+const TRAILING_PUNCTUATION: &[char] =
+    &['.', ',', ';', ')', ']', '}', '>', '"', '\'', '!', '?', '*'];
+
+/// A SPAYD found in a larger text.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SpaydMatch {
+    /// The byte range of the code in the text, including any line breaks
+    /// inside it.
+    pub span: Range<usize>,
+    /// The parsed code.
+    pub spayd: Spayd,
+}
+
+/// Find every SPAYD in a text such as an e-mail body, OCR output or a chat
+/// message.
+///
+/// A code starts with `SPD*` and continues while the characters can be part
+/// of a SPAYD. A space is only kept inside a value if another `*KEY:` field
+/// follows on the same line, so a code at the end of a sentence ends at the
+/// first space. Trailing punctuation such as a full stop is removed.
+///
+/// A code reaching the end of a line continues on the next line, after any
+/// indentation and `>` quote markers, if the line ends with a
+/// quoted-printable soft line break (`=`), the code ends with `*` or `:`,
+/// or the next line's first word contains another `*KEY:` field. If the
+/// joined code isn't valid, the code up to each of the line breaks is tried
+/// instead.
+///
+/// Codes which can't be parsed or validated are skipped.
+/// ```
+/// use spayd::find_spayds;
+///
+/// let email = "Hello,\n\
+///              please pay SPD*1.0*ACC:CZ5855000000001265098001*AM:480.50*CC:C\n\
+///              ZK*MSG:Invoice 2026-123*X-VS:1001.\n\
+///              Thank you.";
+/// let found = find_spayds(email);
+///
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].spayd.field("CC"), Some("CZK"));
+/// assert_eq!(found[0].spayd.field("MSG"), Some("Invoice 2026-123"));
+/// assert_eq!(&email[found[0].span.end - 4..found[0].span.end], "1001");
+/// ```
+pub fn find_spayds(text: &str) -> Vec<SpaydMatch> {
+    let mut matches = Vec::new();
+    let mut from = 0;
+
+    while let Some(offset) = text[from..].find(HEADER) {
+        let start = from + offset;
+        let in_word = text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric());
+        match read_code(text, start).filter(|_| !in_word) {
+            Some(found) => {
+                from = found.span.end;
+                matches.push(found);
+            }
+            None => from = start + HEADER.len(),
+        }
+    }
+    matches
+}
+
+fn is_code_char(b: u8) -> bool {
+    b.is_ascii_graphic()
+}
+
+/// Check if text continues the value of a field and is followed by another
+/// `*KEY:` field, e.g. the rest of a line after a space.
+fn continues_with_field(line: &str) -> bool {
+    let Some(star) = line.find('*') else {
+        return false;
+    };
+    let (value, rest) = (&line[..star], &line[star + 1..]);
+    if value.ends_with("SPD") || !value.bytes().all(|b| b == b' ' || is_code_char(b)) {
+        return false;
+    }
+    let key_length = rest
+        .bytes()
+        .take_while(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || *b == b'-')
+        .count();
+    key_length > 0 && rest.as_bytes().get(key_length) == Some(&b':')
+}
+
+/// The code read so far, if it ended at a line break.
+struct Candidate {
+    code: String,
+    end: usize,
+}
+
+impl Candidate {
+    /// Remove trailing punctuation and parse the code.
+    fn parse(&self, start: usize) -> Option<SpaydMatch> {
+        let code = self.code.trim_end_matches(TRAILING_PUNCTUATION);
+        let end = self.end - (self.code.len() - code.len());
+        code.parse().ok().map(|spayd| SpaydMatch {
+            span: start..end,
+            spayd,
+        })
+    }
+}
+
+/// Read the code starting at `start`, joining wrapped lines.
+fn read_code(text: &str, start: usize) -> Option<SpaydMatch> {
+    let bytes = text.as_bytes();
+    let mut candidates = Vec::new();
+    let mut code = String::new();
+    let mut index = start;
+    let mut end = start;
+
+    loop {
+        let line_end = text[index..]
+            .find(['\r', '\n'])
+            .map_or(text.len(), |offset| index + offset);
+
+        while index < line_end {
+            let b = bytes[index];
+            if b == b' ' {
+                let spaces = text[index..line_end]
+                    .bytes()
+                    .take_while(|b| *b == b' ')
+                    .count();
+                if !continues_with_field(&text[index..line_end]) {
+                    break;
+                }
+                code.push_str(&text[index..index + spaces]);
+                index += spaces;
+            } else if is_code_char(b) {
+                code.push(char::from(b));
+                index += 1;
+                end = index;
+            } else {
+                break;
+            }
+        }
+        candidates.push(Candidate {
+            code: code.clone(),
+            end,
+        });
+        if index < line_end || line_end == text.len() {
+            break;
+        }
+
+        let soft_break = code.ends_with('=');
+        let next = text[line_end..]
+            .strip_prefix("\r\n")
+            .or_else(|| text[line_end..].strip_prefix(['\r', '\n']))
+            .unwrap_or_default();
+        let continuation = next.trim_start_matches([' ', '\t', '>']);
+        let word = continuation
+            .split(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+        let joined = !word.is_empty()
+            && !word.starts_with(HEADER)
+            && (soft_break || code.ends_with(['*', ':']) || continues_with_field(word));
+        if !joined {
+            break;
+        }
+        if soft_break {
+            code.pop();
+        }
+        index = text.len() - continuation.len();
+    }
+
+    candidates
+        .iter()
+        .rev()
+        .find_map(|candidate| candidate.parse(start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACC: &str = "ACC:CZ5855000000001265098001";
+
+    fn codes(text: &str) -> Vec<String> {
+        find_spayds(text)
+            .into_iter()
+            .map(|found| found.spayd.to_string())
+            .collect()
+    }
+
+    fn spans(text: &str) -> Vec<&str> {
+        find_spayds(text)
+            .into_iter()
+            .map(|found| &text[found.span])
+            .collect()
+    }
+
+    #[test]
+    fn single_line() {
+        let text = format!("Pay SPD*1.0*{}*AM:100 now, or SPD*1.0*{}*AM:200.", ACC, ACC);
+        assert_eq!(
+            spans(&text),
+            vec![
+                "SPD*1.0*ACC:CZ5855000000001265098001*AM:100",
+                "SPD*1.0*ACC:CZ5855000000001265098001*AM:200"
+            ]
+        );
+    }
+
+    #[test]
+    fn spaces_in_values() {
+        let text = format!("(SPD*1.0*{}*MSG:Thank you*AM:1) for paying", ACC);
+        let found = find_spayds(&text);
+        assert_eq!(found[0].spayd.field("MSG"), Some("Thank you"));
+        assert_eq!(found[0].spayd.field("AM"), Some("1"));
+
+        let text = format!("SPD*1.0*{}*MSG:Thanks for paying", ACC);
+        assert_eq!(find_spayds(&text)[0].spayd.field("MSG"), Some("Thanks"));
+    }
+
+    #[test]
+    fn wrapped_lines() {
+        let text = format!(
+            "> SPD*1.0*{}*AM:480.5\r\n>  0*CC:CZK*MSG:A\n> B*X-VS:1\n> Regards",
+            ACC
+        );
+        let found = find_spayds(&text);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].spayd.field("AM"), Some("480.50"));
+        assert_eq!(found[0].spayd.field("MSG"), Some("AB"));
+        assert_eq!(found[0].spayd.field("X-VS"), Some("1"));
+        assert!(text[found[0].span.clone()].ends_with("X-VS:1"));
+
+        let text = format!("SPD*1.0*{}*\n  AM:1\n", ACC);
+        assert_eq!(find_spayds(&text)[0].spayd.field("AM"), Some("1"));
+    }
+
+    #[test]
+    fn soft_line_break() {
+        let text = format!("SPD*1.0*{}*MSG:Fak=\ntura 1\n", ACC);
+        assert_eq!(find_spayds(&text)[0].spayd.field("MSG"), Some("Faktura"));
+    }
+
+    #[test]
+    fn not_joined() {
+        let text = format!("SPD*1.0*{}\nSPD*1.0*{}*AM:2\nA*B", ACC, ACC);
+        assert_eq!(
+            codes(&text),
+            vec![
+                "SPD*1.0*ACC:CZ5855000000001265098001",
+                "SPD*1.0*ACC:CZ5855000000001265098001*AM:2"
+            ]
+        );
+
+        let text = format!("SPD*1.0*{}*AM:1\nNext line\n", ACC);
+        assert_eq!(
+            spans(&text),
+            vec!["SPD*1.0*ACC:CZ5855000000001265098001*AM:1"]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_first_line() {
+        let text = format!("SPD*1.0*{}*AM:1\n%*AM:2\n", ACC);
+        assert_eq!(
+            spans(&text),
+            vec!["SPD*1.0*ACC:CZ5855000000001265098001*AM:1"]
+        );
+    }
+
+    #[test]
+    fn invalid_codes() {
+        assert!(find_spayds("SPD*1.0*AM:1 and XSPD*1.0*ACC:CZ5855000000001265098001").is_empty());
+        assert!(find_spayds("SPD*").is_empty());
+        assert_eq!(
+            codes("SPD*SPD*1.0*ACC:CZ5855000000001265098001 ěšč"),
+            vec!["SPD*1.0*ACC:CZ5855000000001265098001"]
+        );
+    }
+}
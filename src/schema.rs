@@ -0,0 +1,212 @@
+use crate::{fields, FieldRegistry};
+use crate::{Spayd, SpaydError, SpaydVersion};
+
+/// The fields defined by one version of the SPAYD standard.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SpaydSchema {
+    version: SpaydVersion,
+    required: &'static [&'static str],
+    known: &'static [&'static str],
+}
+
+/// Schemas for each version of the standard, in version order.
+static SCHEMAS: &[SpaydSchema] = &[SpaydSchema {
+    version: SpaydVersion { major: 1, minor: 0 },
+    required: &[fields::ACCOUNT],
+    known: &[
+        fields::ACCOUNT,
+        fields::ALTERNATIVE_ACCOUNTS,
+        fields::AMOUNT,
+        fields::CURRENCY,
+        fields::REFERENCE,
+        fields::RECIPIENT,
+        fields::DUE_DATE,
+        fields::PAYMENT_TYPE,
+        fields::MESSAGE,
+        fields::CRC32_CHECKSUM,
+        fields::NOTIFICATION_TYPE,
+        fields::NOTIFICATION_ADDRESS,
+        fields::RETRY_DAYS,
+        fields::VARIABLE_SYMBOL,
+        fields::SPECIFIC_SYMBOL,
+        fields::CONSTANT_SYMBOL,
+        fields::PAYER_ID,
+        fields::URL,
+    ],
+}];
+
+/// Non-fatal problems found when checking a SPAYD against its schema.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SpaydWarning {
+    /// The minor version isn't known. The schema of the latest known minor
+    /// version with the same major version is used instead.
+    UnknownMinorVersion(SpaydVersion),
+    /// The field isn't defined by the schema, doesn't use the `X-` prefix
+    /// for extension fields and, with `warnings_with`, hasn't been
+    /// registered as a custom field.
+    UnknownField(String),
+}
+
+impl SpaydSchema {
+    /// All the versions which have a schema.
+    pub fn supported_versions() -> impl Iterator<Item = SpaydVersion> {
+        SCHEMAS.iter().map(|schema| schema.version)
+    }
+
+    /// Find the schema for a version. Unknown minor versions use the latest
+    /// schema with the same major version, along with a warning. Unknown major
+    /// versions can't be handled and fail with `UnsupportedVersion`.
+    pub fn for_version(
+        version: SpaydVersion,
+    ) -> Result<(&'static Self, Option<SpaydWarning>), SpaydError> {
+        if let Some(schema) = SCHEMAS.iter().find(|schema| schema.version == version) {
+            return Ok((schema, None));
+        }
+
+        SCHEMAS
+            .iter()
+            .filter(|schema| schema.version.major == version.major)
+            .max_by_key(|schema| schema.version)
+            .map(|schema| (schema, Some(SpaydWarning::UnknownMinorVersion(version))))
+            .ok_or(SpaydError::UnsupportedVersion(version))
+    }
+
+    /// The version described by this schema.
+    pub fn version(&self) -> SpaydVersion {
+        self.version
+    }
+
+    /// Fields which must be present.
+    pub fn required_fields(&self) -> &'static [&'static str] {
+        self.required
+    }
+
+    /// Fields defined by this version of the standard.
+    pub fn known_fields(&self) -> &'static [&'static str] {
+        self.known
+    }
+
+    /// Check if a field is defined by the standard or is an extension field.
+    pub fn is_known(&self, key: &str) -> bool {
+        key.starts_with(fields::EXTENSION_PREFIX) || self.known.contains(&key)
+    }
+}
+
+impl Spayd {
+    /// Get the schema for this SPAYD's version.
+    pub fn schema(&self) -> Result<&'static SpaydSchema, SpaydError> {
+        SpaydSchema::for_version(self.version()).map(|(schema, _)| schema)
+    }
+
+    /// Ensure that all fields required by the schema for this version are
+    /// present.
+    pub(crate) fn check_required_fields(&self) -> Result<(), SpaydError> {
+        let schema = self.schema()?;
+        match schema
+            .required_fields()
+            .iter()
+            .find(|key| self.field(key).is_none())
+        {
+            Some(key) => Err(SpaydError::RequiredFieldMissing(key.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// List non-fatal problems with the SPAYD, such as an unknown minor
+    /// version or fields which aren't defined by the schema.
+    pub fn warnings(&self) -> Result<Vec<SpaydWarning>, SpaydError> {
+        self.warnings_with(&FieldRegistry::new())
+    }
+
+    /// List non-fatal problems like `warnings`, treating the fields in the
+    /// registry as known.
+    pub fn warnings_with(&self, registry: &FieldRegistry) -> Result<Vec<SpaydWarning>, SpaydError> {
+        let (schema, version_warning) = SpaydSchema::for_version(self.version())?;
+
+        Ok(version_warning
+            .into_iter()
+            .chain(
+                self.iter()
+                    .filter(|(key, _)| !schema.is_known(key) && !registry.is_registered(key))
+                    .map(|(key, _)| SpaydWarning::UnknownField(key.to_owned())),
+            )
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_version() {
+        let (schema, warning) = SpaydSchema::for_version(SpaydVersion::new(1, 0)).unwrap();
+        assert_eq!(schema.version(), SpaydVersion::new(1, 0));
+        assert_eq!(schema.required_fields(), &["ACC"]);
+        assert_eq!(warning, None);
+    }
+
+    #[test]
+    fn unknown_minor_version() {
+        let (schema, warning) = SpaydSchema::for_version(SpaydVersion::new(1, 5)).unwrap();
+        assert_eq!(schema.version(), SpaydVersion::new(1, 0));
+        assert_eq!(
+            warning,
+            Some(SpaydWarning::UnknownMinorVersion(SpaydVersion::new(1, 5)))
+        );
+    }
+
+    #[test]
+    fn unknown_major_version() {
+        assert_eq!(
+            SpaydSchema::for_version(SpaydVersion::new(2, 1)),
+            Err(SpaydError::UnsupportedVersion(SpaydVersion::new(2, 1)))
+        );
+        assert_eq!(
+            "SPD*0.1*ACC:CZ5855000000001265098001".parse::<Spayd>(),
+            Err(SpaydError::UnsupportedVersion(SpaydVersion::new(0, 1)))
+        );
+    }
+
+    #[test]
+    fn known_fields() {
+        let schema = Spayd::empty_v1_0().schema().unwrap();
+        assert!(schema.is_known("MSG"));
+        assert!(schema.is_known("X-VS"));
+        assert!(schema.is_known("X-ORDER"));
+        assert!(!schema.is_known("ORDER"));
+    }
+
+    #[test]
+    fn warnings() {
+        let spayd: Spayd = "SPD*1.1*ACC:CZ5855000000001265098001*ORDER:123*X-ORDER:123"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            spayd.warnings(),
+            Ok(vec![
+                SpaydWarning::UnknownMinorVersion(SpaydVersion::new(1, 1)),
+                SpaydWarning::UnknownField("ORDER".into()),
+            ])
+        );
+
+        let spayd: Spayd = "SPD*1.0*ACC:CZ5855000000001265098001*X-VS:1234"
+            .parse()
+            .unwrap();
+        assert_eq!(spayd.warnings(), Ok(vec![]));
+    }
+
+    #[test]
+    fn registered_field_warnings() {
+        let spayd: Spayd = "SPD*1.0*ACC:CZ5855000000001265098001*ORDER:123"
+            .parse()
+            .unwrap();
+        let mut registry = FieldRegistry::new();
+        registry.register_validator("ORDER", |_| Ok(())).unwrap();
+        assert_eq!(spayd.warnings_with(&registry), Ok(vec![]));
+        assert_eq!(
+            spayd.warnings(),
+            Ok(vec![SpaydWarning::UnknownField("ORDER".into())])
+        );
+    }
+}
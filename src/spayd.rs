@@ -7,7 +7,8 @@ use crate::SpaydError;
 
 /// Version number of the Short Payment Descriptor.
 ///
-/// Currently there is only a standard for version 1.0.
+/// Currently there is only a standard for version 1.0. See `SpaydSchema`
+/// for how other versions are handled.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct SpaydVersion {
     pub major: u32,
@@ -36,7 +37,7 @@ pub struct Spayd {
     fields: SpaydFields,
 }
 
-impl<'a> Spayd {
+impl Spayd {
     /// Create a new SPAYD with the given version number and field values.
     /// Using `new_v1_0` or `empty_v1_0` is preferable for most situations.
     pub fn new<I, K, V>(version: SpaydVersion, fields: I) -> Self
@@ -79,7 +80,9 @@ impl<'a> Spayd {
         self.fields.get(key).map(String::as_ref)
     }
 
-    /// Set the value of the given field.
+    /// Set the value of the given field. The key and value aren't checked
+    /// until `validate` is called, use `try_set_field` to check them
+    /// immediately.
     pub fn set_field<K, V>(&mut self, key: K, value: V)
     where
         K: ToString,
@@ -88,21 +91,42 @@ impl<'a> Spayd {
         self.fields.insert(key.to_string(), value.to_string());
     }
 
-    /// Ensure that all required fields are present. In version 1.0 this
-    /// is only the ACC field.
-    fn check_required_fields(&self) -> Result<(), SpaydError> {
-        if self.field("ACC").is_none() {
-            Err(SpaydError::RequiredFieldMissing("ACC".into()))
-        } else {
-            Ok(())
-        }
+    /// Set the value of the given field, failing with `InvalidKey` if the
+    /// key isn't a valid field name or `InvalidValue` if the value contains
+    /// characters which aren't allowed.
+    pub fn try_set_field<K, V>(&mut self, key: K, value: V) -> Result<(), SpaydError>
+    where
+        K: ToString,
+        V: ToString,
+    {
+        let key = key.to_string();
+        let value = value.to_string();
+        check_key(&key)?;
+        check_value(&key, &value)?;
+        self.fields.insert(key, value);
+        Ok(())
+    }
+
+    /// Ensure that all field names and values are valid.
+    fn check_fields(&self) -> Result<(), SpaydError> {
+        self.iter().try_for_each(|(key, value)| {
+            check_key(key)?;
+            check_value(key, value)
+        })
     }
 
-    /// Ensure that the SPAYD has all required fields and that the CRC
+    /// Ensure that the SPAYD's field names and values are valid, its version
+    /// is supported, it has all the fields required by that version, the
+    /// amount has no more decimal places than the currency allows (if the
+    /// rust_decimal and iso_currency features are enabled) and that the CRC
     /// check is correct (if this feature is enabled).
     pub fn validate(&self) -> Result<(), SpaydError> {
+        self.check_fields()?;
         self.check_required_fields()?;
 
+        #[cfg(all(feature = "rust_decimal", feature = "iso_currency"))]
+        self.check_amount_precision_if_present()?;
+
         #[cfg(feature = "crc32")]
         self.check_crc32()?;
 
@@ -140,7 +164,7 @@ impl<'a> Spayd {
 
         for (k, v) in fields {
             buf.push('*');
-            buf.push_str(&utf8_percent_encode(k, ESCAPED).to_string());
+            buf.push_str(k);
 
             buf.push(':');
             buf.push_str(&utf8_percent_encode(v, ESCAPED).to_string());
@@ -149,8 +173,44 @@ impl<'a> Spayd {
     }
 }
 
+/// Characters which are escaped in values. Non-ASCII characters are always
+/// escaped, and escapes are always written with uppercase hex digits.
 const ESCAPED: &AsciiSet = &CONTROLS.add(b'%').add(b'*');
 
+/// Check if a field name is allowed by the standard. Names must be
+/// non-empty and may only contain uppercase letters, digits and `-`.
+pub fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'-')
+}
+
+pub(crate) fn check_key(key: &str) -> Result<(), SpaydError> {
+    if is_valid_key(key) {
+        Ok(())
+    } else {
+        Err(SpaydError::InvalidKey(key.into()))
+    }
+}
+
+/// Check if a field value only contains characters allowed by the
+/// standard. Values must be non-empty, as an empty value can't be parsed,
+/// and may contain any characters except control characters, which can't
+/// be displayed or typed by scanners. Characters special to the SPAYD
+/// format, such as `*`, are escaped when formatting.
+pub fn is_valid_value(value: &str) -> bool {
+    !value.is_empty() && !value.chars().any(char::is_control)
+}
+
+pub(crate) fn check_value(key: &str, value: &str) -> Result<(), SpaydError> {
+    if is_valid_value(value) {
+        Ok(())
+    } else {
+        Err(SpaydError::InvalidValue(key.into()))
+    }
+}
+
 impl Display for Spayd {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -232,4 +292,86 @@ mod tests {
         let spayd = Spayd::new_v1_0(vec![("MSG", "PŘÍKLAD")]);
         assert_eq!(spayd.to_string(), "SPD*1.0*MSG:P%C5%98%C3%8DKLAD");
     }
+
+    #[test]
+    fn valid_keys() {
+        assert!(is_valid_key("ACC"));
+        assert!(is_valid_key("ALT-ACC"));
+        assert!(is_valid_key("CRC32"));
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("msg"));
+        assert!(!is_valid_key("X_VS"));
+        assert!(!is_valid_key("A:B"));
+    }
+
+    #[test]
+    fn try_set_invalid_key() {
+        let mut spayd = Spayd::empty_v1_0();
+        assert_eq!(
+            spayd.try_set_field("Msg", "hello"),
+            Err(SpaydError::InvalidKey("Msg".into()))
+        );
+        assert_eq!(spayd.try_set_field("MSG", "hello"), Ok(()));
+        assert_eq!(spayd.field("MSG"), Some("hello"));
+    }
+
+    #[test]
+    fn set_invalid_key() {
+        let mut spayd = Spayd::new_v1_0(vec![("ACC", "CZ5855000000001265098001")]);
+        spayd.set_field("X VS", "1234");
+        assert_eq!(spayd.field("X VS"), Some("1234"));
+        assert_eq!(spayd.validate(), Err(SpaydError::InvalidKey("X VS".into())));
+    }
+
+    #[test]
+    fn valid_values() {
+        assert!(!is_valid_value(""));
+        assert!(is_valid_value("Platba za zboží *1/2* 100%"));
+        assert!(!is_valid_value("line\nbreak"));
+        assert!(!is_valid_value("tab\t"));
+        assert!(!is_valid_value("\u{85}"));
+    }
+
+    #[test]
+    fn invalid_values() {
+        let mut spayd = Spayd::new_v1_0(vec![("ACC", "CZ5855000000001265098001")]);
+        assert_eq!(
+            spayd.try_set_field("MSG", "a\u{7f}b"),
+            Err(SpaydError::InvalidValue("MSG".into()))
+        );
+        assert_eq!(spayd.field("MSG"), None);
+
+        spayd.set_field("MSG", "a\u{7f}b");
+        assert_eq!(
+            spayd.validate(),
+            Err(SpaydError::InvalidValue("MSG".into()))
+        );
+        assert_eq!(
+            "SPD*1.0*ACC:CZ5855000000001265098001*MSG:a%0Ab".parse::<Spayd>(),
+            Err(SpaydError::InvalidValue("MSG".into()))
+        );
+    }
+
+    #[test]
+    fn empty_values() {
+        let mut spayd = Spayd::new_v1_0(vec![("ACC", "CZ5855000000001265098001")]);
+        assert_eq!(
+            spayd.try_set_field("MSG", ""),
+            Err(SpaydError::InvalidValue("MSG".into()))
+        );
+        assert_eq!(spayd.to_string(), "SPD*1.0*ACC:CZ5855000000001265098001");
+
+        spayd.set_field("MSG", "");
+        assert_eq!(
+            spayd.validate(),
+            Err(SpaydError::InvalidValue("MSG".into()))
+        );
+        assert!(spayd.to_string().parse::<Spayd>().is_err());
+    }
+
+    #[test]
+    fn validate_keys() {
+        let spayd = Spayd::new_v1_0(vec![("ACC", "CZ5855000000001265098001"), ("*", "1")]);
+        assert_eq!(spayd.validate(), Err(SpaydError::InvalidKey("*".into())));
+    }
 }
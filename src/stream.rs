@@ -0,0 +1,304 @@
+use crate::{fields, is_valid_key, Spayd, SpaydError, SpaydSchema, SpaydVersion};
+
+/// The start of every SPAYD.
+const HEADER: &str = "SPD*";
+
+/// Line breaks which end a code.
+const LINE_BREAKS: [char; 2] = ['\r', '\n'];
+
+/// The state of a `SpaydStreamParser` after a chunk of input.
+#[derive(PartialEq, Debug)]
+pub enum StreamStatus {
+    /// The input so far is the start of a SPAYD, but not yet a valid one.
+    Partial,
+    /// The input so far is a valid SPAYD, but more fields may follow. Call
+    /// `SpaydStreamParser::finish` once no more input arrives.
+    Ready,
+    /// A code ended with a line break or passed its CRC32 check. The parser
+    /// is ready for the next code.
+    Complete(Spayd),
+    /// The input can't become a valid SPAYD. Line breaks end an invalid
+    /// code too, otherwise the parser keeps it until `reset` is called.
+    Invalid(SpaydError),
+}
+
+/// Parses SPAYDs arriving in chunks, e.g. from a barcode scanner typing the
+/// code character by character.
+///
+/// After each chunk the parser reports whether the input so far can still
+/// become a valid SPAYD, already is one, or can't be one. A code is complete
+/// when a line break follows it or, with the `crc32` feature, as soon as it
+/// has a CRC32 field and passes its check. Scanners which send no line break
+/// and codes without CRC32 need `finish` to be called, e.g. after a short
+/// time without input.
+///
+/// Any input after the line break ending a code is kept as the start of the
+/// next code, and can be checked by pushing an empty chunk.
+/// ```
+/// use spayd::{SpaydStreamParser, StreamStatus};
+///
+/// let mut parser = SpaydStreamParser::new();
+/// assert_eq!(parser.push("SPD*1.0*AC"), StreamStatus::Partial);
+/// assert_eq!(parser.push("C:CZ5855000000001265098001*AM:100"), StreamStatus::Ready);
+///
+/// // No more input arrived for a while.
+/// assert_eq!(parser.finish().unwrap().field("AM"), Some("100"));
+///
+/// assert!(matches!(parser.push("SPD*1.0*ACC:CZ5855000000001265098001\r\n"), StreamStatus::Complete(_)));
+/// assert!(matches!(parser.push("SPD*1.0*ACC=1"), StreamStatus::Invalid(_)));
+/// ```
+#[derive(Default, Debug)]
+pub struct SpaydStreamParser {
+    buffer: String,
+}
+
+impl SpaydStreamParser {
+    /// A parser with no input.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The input received since the last code was completed.
+    pub fn buffer(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Discard the input received so far.
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Add a chunk of input and check the code received so far. Line breaks
+    /// before a code are ignored.
+    pub fn push(&mut self, chunk: &str) -> StreamStatus {
+        self.buffer.push_str(chunk);
+        self.skip_line_breaks();
+
+        if let Some(end) = self.buffer.find(LINE_BREAKS) {
+            let code: String = self.buffer.drain(..end).collect();
+            self.skip_line_breaks();
+            return match code.parse() {
+                Ok(spayd) => StreamStatus::Complete(spayd),
+                Err(error) => StreamStatus::Invalid(error),
+            };
+        }
+
+        match self.buffer.parse::<Spayd>() {
+            Ok(spayd)
+                if cfg!(feature = "crc32") && spayd.field(fields::CRC32_CHECKSUM).is_some() =>
+            {
+                self.reset();
+                StreamStatus::Complete(spayd)
+            }
+            Ok(_) => StreamStatus::Ready,
+            Err(_) if is_prefix(&self.buffer) => StreamStatus::Partial,
+            Err(error) => StreamStatus::Invalid(error),
+        }
+    }
+
+    /// End the current code, e.g. after a timeout, and parse it. The parser
+    /// is ready for the next code afterwards, whether parsing succeeds or
+    /// not.
+    pub fn finish(&mut self) -> Result<Spayd, SpaydError> {
+        let code = std::mem::take(&mut self.buffer);
+        code.parse()
+    }
+
+    fn skip_line_breaks(&mut self) {
+        let breaks = self.buffer.len() - self.buffer.trim_start_matches(LINE_BREAKS).len();
+        self.buffer.drain(..breaks);
+    }
+}
+
+/// Check if more input could make the text a syntactically valid SPAYD with
+/// a supported version.
+fn is_prefix(text: &str) -> bool {
+    if !text.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+        return false;
+    }
+    if text.len() <= HEADER.len() {
+        return HEADER.starts_with(text);
+    }
+    let Some(rest) = text.strip_prefix(HEADER) else {
+        return false;
+    };
+
+    let Some((version, fields)) = rest.split_once('*') else {
+        return is_version_prefix(rest);
+    };
+    if !is_version(version) {
+        return false;
+    }
+    let segments: Vec<&str> = fields.split('*').collect();
+    let Some((last, complete)) = segments.split_last() else {
+        return true;
+    };
+    complete.iter().all(|segment| is_field(segment, true)) && is_field(last, false)
+}
+
+fn is_digits(text: &str) -> bool {
+    text.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Check if text could be the start of a version, e.g. "1" or "1.".
+fn is_version_prefix(text: &str) -> bool {
+    match text.split_once('.') {
+        Some((major, minor)) => !major.is_empty() && is_digits(major) && is_digits(minor),
+        None => is_digits(text),
+    }
+}
+
+/// Check if text is a supported version.
+fn is_version(text: &str) -> bool {
+    let Some((major, minor)) = text.split_once('.') else {
+        return false;
+    };
+    if !is_digits(major) || !is_digits(minor) {
+        return false;
+    }
+    match (major.parse(), minor.parse()) {
+        (Ok(major), Ok(minor)) => SpaydSchema::for_version(SpaydVersion::new(major, minor)).is_ok(),
+        _ => false,
+    }
+}
+
+/// Check if text is a field, or could be the start of one if it isn't
+/// complete.
+fn is_field(text: &str, complete: bool) -> bool {
+    match text.split_once(':') {
+        Some((key, value)) => {
+            is_valid_key(key) && is_value(value, complete) && !(complete && value.is_empty())
+        }
+        None => !complete && (text.is_empty() || is_valid_key(text)),
+    }
+}
+
+/// Check the escapes of a value. An incomplete value may end in the middle
+/// of an escape.
+fn is_value(text: &str, complete: bool) -> bool {
+    let bytes = text.as_bytes();
+    text.match_indices('%').all(|(i, _)| {
+        let escape = &bytes[i + 1..bytes.len().min(i + 3)];
+        escape.iter().all(u8::is_ascii_hexdigit) && (escape.len() == 2 || !complete)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "SPD*1.0*ACC:CZ5855000000001265098001*AM:100.00*CC:CZK*CRC32:AAD80227";
+
+    /// Push text one character at a time, returning the status after each.
+    fn push_chars(parser: &mut SpaydStreamParser, text: &str) -> Vec<StreamStatus> {
+        text.chars()
+            .map(|c| parser.push(c.encode_utf8(&mut [0; 4])))
+            .collect()
+    }
+
+    #[test]
+    fn prefixes() {
+        for prefix in [
+            "",
+            "S",
+            "SPD*",
+            "SPD*1",
+            "SPD*1.",
+            "SPD*1.0*",
+            "SPD*1.0*AC",
+            "SPD*1.0*ACC:",
+            "SPD*1.0*ACC:CZ5855000000001265098001*MSG:%",
+            "SPD*1.0*ACC:CZ5855000000001265098001*MSG:%C",
+            "SPD*1.0*ACC:CZ5855000000001265098001*MSG:%C4%8C*",
+        ] {
+            assert!(is_prefix(prefix), "{prefix}");
+        }
+    }
+
+    #[test]
+    fn not_prefixes() {
+        for text in [
+            "X",
+            "SPX",
+            "SPD**",
+            "SPD*.",
+            "SPD*1.0.",
+            "SPD*9.0*",
+            "SPD*1.0**",
+            "SPD*1.0*acc:",
+            "SPD*1.0*ACC*",
+            "SPD*1.0*ACC:*",
+            "SPD*1.0*MSG:%X",
+            "SPD*1.0*MSG:%C*",
+            "SPD*1.0*MSG:Čau",
+            "SPD*1.0*MSG:a\tb",
+        ] {
+            assert!(!is_prefix(text), "{text}");
+        }
+    }
+
+    #[test]
+    fn completes_on_crc32() {
+        let mut parser = SpaydStreamParser::new();
+        let statuses = push_chars(&mut parser, CODE);
+        let (last, rest) = statuses.split_last().unwrap();
+
+        if cfg!(feature = "crc32") {
+            assert_eq!(*last, StreamStatus::Complete(CODE.parse().unwrap()));
+            assert_eq!(parser.buffer(), "");
+        } else {
+            assert_eq!(*last, StreamStatus::Ready);
+        }
+
+        assert!(rest
+            .iter()
+            .all(|status| matches!(status, StreamStatus::Partial | StreamStatus::Ready)));
+        assert_eq!(rest[35], StreamStatus::Ready);
+    }
+
+    #[test]
+    fn completes_on_line_break() {
+        let mut parser = SpaydStreamParser::new();
+        assert_eq!(parser.push("\r\nSPD*1.0*AC"), StreamStatus::Partial);
+        assert_eq!(
+            parser.push("C:CZ5855000000001265098001\r\nSPD*1.0"),
+            StreamStatus::Complete("SPD*1.0*ACC:CZ5855000000001265098001".parse().unwrap())
+        );
+        assert_eq!(parser.buffer(), "SPD*1.0");
+        assert_eq!(parser.push(""), StreamStatus::Partial);
+
+        assert!(matches!(
+            parser.push("*AM:1\n"),
+            StreamStatus::Invalid(SpaydError::RequiredFieldMissing(_))
+        ));
+        assert_eq!(parser.buffer(), "");
+    }
+
+    #[test]
+    fn invalid_until_reset() {
+        let mut parser = SpaydStreamParser::new();
+        assert!(matches!(
+            parser.push("SPD*1.0*ACC:CZ58%G"),
+            StreamStatus::Invalid(SpaydError::MalformedEscape(_))
+        ));
+        assert!(matches!(parser.push("5"), StreamStatus::Invalid(_)));
+
+        parser.reset();
+        assert_eq!(parser.push("SPD*"), StreamStatus::Partial);
+        assert!(matches!(parser.push("X"), StreamStatus::Invalid(_)));
+        assert!(matches!(parser.push("\n"), StreamStatus::Invalid(_)));
+        assert_eq!(parser.push("SPD*"), StreamStatus::Partial);
+    }
+
+    #[test]
+    fn finish() {
+        let mut parser = SpaydStreamParser::new();
+        parser.push("SPD*1.0*ACC:CZ5855000000001265098001*AM:1");
+        assert_eq!(parser.finish().unwrap().field("AM"), Some("1"));
+        assert_eq!(parser.buffer(), "");
+
+        parser.push("SPD*1.0*AM:1");
+        assert!(parser.finish().is_err());
+        assert!(parser.finish().is_err());
+    }
+}
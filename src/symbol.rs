@@ -0,0 +1,229 @@
+use crate::{fields, Spayd, SpaydError};
+
+/// Maximum number of digits in a Czech variable symbol.
+const MAX_LENGTH: usize = 10;
+
+/// Check digit appended to a generated variable symbol.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CheckDigit {
+    /// No check digit.
+    #[default]
+    None,
+    /// Weighted modulo 11 as used for Czech company numbers (IČO): digits
+    /// are weighted 2, 3, 4... from the right and the check digit is
+    /// `(11 - sum % 11) % 10`.
+    Mod11,
+    /// The Luhn algorithm used for payment card numbers.
+    Luhn,
+}
+
+impl CheckDigit {
+    /// Calculate the check digit for a string of digits.
+    fn calculate(&self, digits: &str) -> Option<u32> {
+        let values = digits.chars().rev().map(|c| c.to_digit(10).unwrap_or(0));
+        match self {
+            Self::None => None,
+            Self::Mod11 => {
+                let sum: u32 = values.zip(2..).map(|(d, weight)| d * weight).sum();
+                Some((11 - sum % 11) % 10)
+            }
+            Self::Luhn => {
+                let sum: u32 = values
+                    .enumerate()
+                    .map(|(i, d)| match (i % 2 == 0, d * 2) {
+                        (true, doubled) if doubled > 9 => doubled - 9,
+                        (true, doubled) => doubled,
+                        (false, _) => d,
+                    })
+                    .sum();
+                Some((10 - sum % 10) % 10)
+            }
+        }
+    }
+}
+
+/// Creates variable symbols (X-VS field) from invoice numbers.
+///
+/// The digits of the invoice number are kept and everything else is dropped,
+/// so "2026-00123" becomes "202600123". A fixed prefix can be put in front,
+/// the number can be padded with zeros to a minimum width and a check digit
+/// can be added. The result must fit in the 10 digits of a variable symbol.
+/// ```
+/// use spayd::{CheckDigit, VariableSymbolGenerator};
+///
+/// let generator = VariableSymbolGenerator::new().with_check_digit(CheckDigit::Luhn);
+/// let symbol = generator.generate("2026-00123").unwrap();
+/// assert_eq!(symbol, "2026001236");
+/// assert!(generator.validate(&symbol).is_ok());
+/// assert!(generator.validate("2026001231").is_err());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct VariableSymbolGenerator {
+    prefix: String,
+    width: usize,
+    check_digit: CheckDigit,
+}
+
+impl VariableSymbolGenerator {
+    /// A generator which only keeps the digits of the invoice number.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Put digits in front of every variable symbol. Anything other than
+    /// digits is dropped.
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.chars().filter(char::is_ascii_digit).collect();
+        self
+    }
+
+    /// Pad the invoice number with leading zeros to at least this many
+    /// digits. The prefix and check digit aren't counted.
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Append a check digit.
+    pub fn with_check_digit(mut self, check_digit: CheckDigit) -> Self {
+        self.check_digit = check_digit;
+        self
+    }
+
+    /// Create the variable symbol for an invoice number. Fails with
+    /// `ConvertError` if the invoice number has no digits or the variable
+    /// symbol would be longer than 10 digits.
+    pub fn generate(&self, invoice: &str) -> Result<String, SpaydError> {
+        let digits: String = invoice.chars().filter(char::is_ascii_digit).collect();
+        if digits.is_empty() {
+            return Err(SpaydError::ConvertError(invoice.into()));
+        }
+
+        let mut symbol = format!("{}{:0>width$}", self.prefix, digits, width = self.width);
+        if let Some(check) = self.check_digit.calculate(&symbol) {
+            symbol.push(char::from_digit(check, 10).unwrap_or('0'));
+        }
+
+        if symbol.len() > MAX_LENGTH {
+            Err(SpaydError::ConvertError(invoice.into()))
+        } else {
+            Ok(symbol)
+        }
+    }
+
+    /// Check that a variable symbol could have come from this generator:
+    /// it's 1 to 10 digits, starts with the prefix and has a correct check
+    /// digit. This catches most mistyped symbols when matching payments.
+    pub fn validate(&self, symbol: &str) -> Result<(), SpaydError> {
+        let error = || SpaydError::ConvertError(symbol.into());
+        if symbol.is_empty()
+            || symbol.len() > MAX_LENGTH
+            || !symbol.bytes().all(|b| b.is_ascii_digit())
+            || !symbol.starts_with(&self.prefix)
+        {
+            return Err(error());
+        }
+
+        if self.check_digit == CheckDigit::None {
+            return Ok(());
+        }
+        let (payload, check) = symbol.split_at(symbol.len() - 1);
+        let expected = self
+            .check_digit
+            .calculate(payload)
+            .and_then(|c| char::from_digit(c, 10));
+        if payload.len() > self.prefix.len() && check.chars().next() == expected {
+            Ok(())
+        } else {
+            Err(error())
+        }
+    }
+}
+
+impl Spayd {
+    /// Set the X-VS field to the variable symbol for an invoice number.
+    pub fn set_variable_symbol_for(
+        &mut self,
+        generator: &VariableSymbolGenerator,
+        invoice: &str,
+    ) -> Result<(), SpaydError> {
+        let symbol = generator.generate(invoice)?;
+        self.set_field(fields::VARIABLE_SYMBOL, symbol);
+        Ok(())
+    }
+
+    /// Check the X-VS field with a generator's validator.
+    pub fn check_variable_symbol(
+        &self,
+        generator: &VariableSymbolGenerator,
+    ) -> Result<(), SpaydError> {
+        match self.field(fields::VARIABLE_SYMBOL) {
+            Some(symbol) => generator.validate(symbol),
+            None => Err(SpaydError::FieldMissing(fields::VARIABLE_SYMBOL.into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_only() {
+        let generator = VariableSymbolGenerator::new();
+        assert_eq!(generator.generate("2026-00123"), Ok("202600123".into()));
+        assert_eq!(generator.generate("FV/2026/7"), Ok("20267".into()));
+        assert!(generator.generate("ABC").is_err());
+        assert!(generator.generate("2026-0000012345").is_err());
+    }
+
+    #[test]
+    fn prefix_and_width() {
+        let generator = VariableSymbolGenerator::new()
+            .with_prefix("9")
+            .with_width(6);
+        assert_eq!(generator.generate("123"), Ok("9000123".into()));
+        assert_eq!(generator.validate("9000123"), Ok(()));
+        assert!(generator.validate("8000123").is_err());
+    }
+
+    #[test]
+    fn mod11() {
+        let generator = VariableSymbolGenerator::new().with_check_digit(CheckDigit::Mod11);
+        // Czech company number 25596641 has check digit 1
+        assert_eq!(generator.generate("2559664"), Ok("25596641".into()));
+        assert_eq!(generator.validate("25596641"), Ok(()));
+        assert!(generator.validate("25596642").is_err());
+        // Swapped digits
+        assert!(generator.validate("52596641").is_err());
+    }
+
+    #[test]
+    fn luhn() {
+        let generator = VariableSymbolGenerator::new().with_check_digit(CheckDigit::Luhn);
+        assert_eq!(
+            generator.generate("7992739871"),
+            Err(SpaydError::ConvertError("7992739871".into()))
+        );
+        assert_eq!(generator.generate("799273987"), Ok("7992739875".into()));
+        assert_eq!(generator.validate("7992739875"), Ok(()));
+        assert!(generator.validate("7992739870").is_err());
+        assert!(generator.validate("0").is_err());
+    }
+
+    #[test]
+    fn spayd_field() {
+        let generator = VariableSymbolGenerator::new().with_check_digit(CheckDigit::Mod11);
+        let mut spayd = Spayd::empty_v1_0();
+        assert_eq!(
+            spayd.check_variable_symbol(&generator),
+            Err(SpaydError::FieldMissing("X-VS".into()))
+        );
+
+        spayd
+            .set_variable_symbol_for(&generator, "2559664")
+            .unwrap();
+        assert_eq!(spayd.field("X-VS"), Some("25596641"));
+        assert_eq!(spayd.check_variable_symbol(&generator), Ok(()));
+    }
+}
@@ -0,0 +1,262 @@
+//! WebAssembly bindings for using SPAYD values from JavaScript.
+//!
+//! The `Spayd` class wraps a SPAYD value. Failures are thrown as
+//! `SpaydError` objects with a `kind` (the name of the Rust error variant),
+//! a human readable `message` and, where relevant, the `field` involved.
+//! Amounts and dates are passed as strings ("480.50", "2023-10-31") so that
+//! no precision is lost in conversion to JavaScript numbers.
+//!
+//! The module is built with
+//! `cargo rustc --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib`
+//! followed by `wasm-bindgen` on the resulting `.wasm` file.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Spayd, SpaydError};
+
+/// An error thrown to JavaScript.
+#[wasm_bindgen(js_name = SpaydError, getter_with_clone)]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct JsSpaydError {
+    /// Name of the error variant, e.g. "RequiredFieldMissing".
+    pub kind: String,
+    /// Description of the error.
+    pub message: String,
+    /// The field involved in the error, if any.
+    pub field: Option<String>,
+}
+
+impl From<SpaydError> for JsSpaydError {
+    fn from(error: SpaydError) -> Self {
+        let field = match &error {
+            SpaydError::RequiredFieldMissing(field)
+            | SpaydError::FieldMissing(field)
+            | SpaydError::InvalidKey(field)
+            | SpaydError::InvalidValue(field) => Some(field),
+            _ => None,
+        };
+        Self {
+            kind: error.kind().to_owned(),
+            message: error.to_string(),
+            field: field.cloned(),
+        }
+    }
+}
+
+type JsResult<T> = Result<T, JsSpaydError>;
+
+/// A SPAYD value exposed to JavaScript.
+#[wasm_bindgen(js_name = Spayd)]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct JsSpayd {
+    inner: Spayd,
+}
+
+#[wasm_bindgen(js_class = Spayd)]
+impl JsSpayd {
+    /// Create an empty version 1.0 SPAYD.
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            inner: Spayd::empty_v1_0(),
+        }
+    }
+
+    /// Parse and validate SPAYD text.
+    pub fn parse(text: &str) -> JsResult<JsSpayd> {
+        Ok(Self {
+            inner: text.parse()?,
+        })
+    }
+
+    /// Ensure that the SPAYD has all required fields and that the CRC
+    /// check is correct.
+    pub fn validate(&self) -> JsResult<()> {
+        Ok(self.inner.validate()?)
+    }
+
+    /// Validate the SPAYD and format it as text.
+    pub fn build(&self) -> JsResult<String> {
+        self.validate()?;
+        Ok(self.inner.to_string())
+    }
+
+    /// Format the SPAYD as text without validating it.
+    #[wasm_bindgen(js_name = toString)]
+    pub fn to_js_string(&self) -> String {
+        self.inner.to_string()
+    }
+
+    /// The version number, e.g. "1.0".
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> String {
+        let version = self.inner.version();
+        format!("{}.{}", version.major, version.minor)
+    }
+
+    /// Get the value of the given field.
+    pub fn field(&self, key: &str) -> Option<String> {
+        self.inner.field(key).map(str::to_owned)
+    }
+
+    /// Set the value of the given field.
+    #[wasm_bindgen(js_name = setField)]
+    pub fn set_field(&mut self, key: &str, value: &str) -> JsResult<()> {
+        Ok(self.inner.try_set_field(key, value)?)
+    }
+
+    /// The names of all fields present.
+    pub fn keys(&self) -> Vec<String> {
+        self.inner.iter().map(|(k, _)| k.to_owned()).collect()
+    }
+
+    /// The IBAN of the main account.
+    #[wasm_bindgen(getter)]
+    pub fn iban(&self) -> JsResult<String> {
+        Ok(self.inner.account()?.iban)
+    }
+
+    /// The BIC of the main account, if one is given.
+    #[wasm_bindgen(getter)]
+    pub fn bic(&self) -> JsResult<Option<String>> {
+        Ok(self.inner.account()?.bic)
+    }
+
+    /// Set the main account from an IBAN and an optional BIC.
+    #[wasm_bindgen(js_name = setAccount)]
+    pub fn set_account(&mut self, iban: &str, bic: Option<String>) {
+        let account = match bic {
+            Some(bic) => crate::IbanBic::iban_bic(iban, bic),
+            None => crate::IbanBic::iban_only(iban),
+        };
+        self.inner.set_account(account);
+    }
+
+    /// Alternative accounts formatted as "IBAN" or "IBAN+BIC".
+    #[wasm_bindgen(js_name = alternativeAccounts)]
+    pub fn alternative_accounts(&self) -> JsResult<Vec<String>> {
+        Ok(self
+            .inner
+            .alternative_accounts()?
+            .iter()
+            .map(ToString::to_string)
+            .collect())
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+#[wasm_bindgen(js_class = Spayd)]
+impl JsSpayd {
+    /// The payment amount as a decimal string.
+    #[wasm_bindgen(getter)]
+    pub fn amount(&self) -> JsResult<String> {
+        Ok(self.inner.amount()?.to_string())
+    }
+
+    /// Set the payment amount from a decimal string.
+    #[wasm_bindgen(js_name = setAmount)]
+    pub fn set_amount(&mut self, amount: &str) -> JsResult<()> {
+        let amount = amount
+            .parse()
+            .or(Err(SpaydError::ConvertError(amount.into())))?;
+        self.inner.set_amount(&amount);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "iso_currency")]
+#[wasm_bindgen(js_class = Spayd)]
+impl JsSpayd {
+    /// The ISO 4217 currency code.
+    #[wasm_bindgen(getter)]
+    pub fn currency(&self) -> JsResult<String> {
+        Ok(self.inner.currency()?.code().to_owned())
+    }
+
+    /// Set the currency from an ISO 4217 code.
+    #[wasm_bindgen(js_name = setCurrency)]
+    pub fn set_currency(&mut self, code: &str) -> JsResult<()> {
+        let currency =
+            iso_currency::Currency::from_code(code).ok_or(SpaydError::ConvertError(code.into()))?;
+        self.inner.set_currency(currency);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[wasm_bindgen(js_class = Spayd)]
+impl JsSpayd {
+    /// The due date as an ISO 8601 date string.
+    #[wasm_bindgen(getter, js_name = dueDate)]
+    pub fn due_date(&self) -> JsResult<String> {
+        Ok(self.inner.due_date()?.format("%Y-%m-%d").to_string())
+    }
+
+    /// Set the due date from an ISO 8601 date string.
+    #[wasm_bindgen(js_name = setDueDate)]
+    pub fn set_due_date(&mut self, date: &str) -> JsResult<()> {
+        let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .or(Err(SpaydError::ConvertError(date.into())))?;
+        self.inner.set_due_date(&date);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_read() {
+        let spayd =
+            JsSpayd::parse("SPD*1.0*ACC:CZ5855000000001265098001+RZBCCZPP*AM:480.50").unwrap();
+        assert_eq!(spayd.version(), "1.0");
+        assert_eq!(spayd.field("AM"), Some("480.50".into()));
+        assert_eq!(spayd.iban(), Ok("CZ5855000000001265098001".into()));
+        assert_eq!(spayd.bic(), Ok(Some("RZBCCZPP".into())));
+        assert_eq!(spayd.keys(), vec!["ACC", "AM"]);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = JsSpayd::parse("SPD*1.0*AM:480.50").unwrap_err();
+        assert_eq!(error.kind, "RequiredFieldMissing");
+        assert_eq!(error.field, Some("ACC".into()));
+        assert_eq!(error.message, "the required field 'ACC' is missing");
+    }
+
+    #[test]
+    fn build() {
+        let mut spayd = JsSpayd::new();
+        assert_eq!(spayd.build().unwrap_err().kind, "RequiredFieldMissing");
+
+        spayd.set_account("CZ5855000000001265098001", None);
+        spayd.set_field("MSG", "Hello").unwrap();
+        assert_eq!(
+            spayd.set_field("msg", "Hello").unwrap_err().kind,
+            "InvalidKey"
+        );
+        assert_eq!(
+            spayd.build(),
+            Ok("SPD*1.0*ACC:CZ5855000000001265098001*MSG:Hello".into())
+        );
+    }
+
+    #[cfg(all(feature = "rust_decimal", feature = "iso_currency", feature = "chrono"))]
+    #[test]
+    fn typed_accessors() {
+        let mut spayd = JsSpayd::new();
+        spayd.set_amount("250.00").unwrap();
+        spayd.set_currency("EUR").unwrap();
+        spayd.set_due_date("2023-10-31").unwrap();
+
+        assert_eq!(spayd.amount(), Ok("250.00".into()));
+        assert_eq!(spayd.currency(), Ok("EUR".into()));
+        assert_eq!(spayd.due_date(), Ok("2023-10-31".into()));
+        assert_eq!(spayd.field("DT"), Some("20231031".into()));
+
+        let error = spayd.set_due_date("31.10.2023").unwrap_err();
+        assert_eq!(error.kind, "ConvertError");
+    }
+}
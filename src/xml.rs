@@ -0,0 +1,30 @@
+use chrono::NaiveDate;
+use quick_xml::events::BytesStart;
+
+/// Convert a byte offset into a line number starting from 1.
+pub(crate) fn line_at(xml: &str, offset: usize) -> usize {
+    xml.as_bytes()[..offset.min(xml.len())]
+        .iter()
+        .filter(|b| **b == b'\n')
+        .count()
+        + 1
+}
+
+/// Check if the innermost elements of a path have the given local names.
+pub(crate) fn path_ends_with(path: &[String], suffix: &[&str]) -> bool {
+    path.len() >= suffix.len() && path[path.len() - suffix.len()..].iter().eq(suffix.iter())
+}
+
+/// Parse an ISO date or the date part of a date and time.
+pub(crate) fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Read the `Ccy` attribute of an amount.
+pub(crate) fn currency_attribute(start: &BytesStart) -> Option<String> {
+    start
+        .try_get_attribute("Ccy")
+        .ok()
+        .flatten()
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}